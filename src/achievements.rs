@@ -0,0 +1,262 @@
+use std::collections::BTreeSet;
+use std::fs;
+use macroquad::prelude::*;
+use crate::background;
+use crate::events::GameEvent;
+use crate::menu::Button;
+use crate::navigation::Navigation;
+
+const ACHIEVEMENTS_FILE: &str = "achievements.cfg";
+const TOAST_DURATION: f64 = 4.0;
+const ROW_HEIGHT: f32 = 50.0;
+
+pub(crate) enum Condition {
+    // Kills over every run ever played
+    TotalKills(u32),
+    KillKind(&'static str),
+    ReachWave(u32),
+    ReachLevel(u32),
+    BuyItem(&'static str),
+    Win,
+    // Win without buying a single item with the tag during the run
+    WinWithoutTag(&'static str),
+}
+
+pub(crate) struct Achievement {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub condition: Condition,
+}
+
+pub const ACHIEVEMENTS: [Achievement; 10] = [
+    Achievement {
+        id: "first_blood",
+        name: "First Blood",
+        description: "Kill your first enemy",
+        condition: Condition::TotalKills(1),
+    },
+    Achievement {
+        id: "giant_slayer",
+        name: "Giant Slayer",
+        description: "Kill an elite enemy",
+        condition: Condition::KillKind("Elite"),
+    },
+    Achievement {
+        id: "warming_up",
+        name: "Warming Up",
+        description: "Reach wave 5",
+        condition: Condition::ReachWave(5),
+    },
+    Achievement {
+        id: "veteran",
+        name: "Veteran",
+        description: "Reach wave 10",
+        condition: Condition::ReachWave(10),
+    },
+    Achievement {
+        id: "seasoned",
+        name: "Seasoned",
+        description: "Reach level 10 in a single run",
+        condition: Condition::ReachLevel(10),
+    },
+    Achievement {
+        id: "exterminator",
+        name: "Exterminator",
+        description: "Kill 1000 enemies over all runs",
+        condition: Condition::TotalKills(1000),
+    },
+    Achievement {
+        id: "going_nuclear",
+        name: "Going Nuclear",
+        description: "Buy Nuclear ammo",
+        condition: Condition::BuyItem("Nuclear ammo"),
+    },
+    Achievement {
+        id: "glass_cannon",
+        name: "Glass Cannon",
+        description: "Win a run without buying defense items",
+        condition: Condition::WinWithoutTag("defense"),
+    },
+    Achievement {
+        id: "champion",
+        name: "Champion",
+        description: "Survive the final wave",
+        condition: Condition::Win,
+    },
+    Achievement {
+        id: "beyond_the_end",
+        name: "Beyond the End",
+        description: "Reach endless wave 25",
+        condition: Condition::ReachWave(25),
+    },
+];
+
+// Progress towards the achievements, what has been unlocked, and the toasts still on screen
+pub(crate) struct Achievements {
+    unlocked: BTreeSet<&'static str>,
+    total_kills: u32,
+    // Tags of every item bought in the current run
+    run_tags: BTreeSet<String>,
+    // Achievements waiting to be shown and when they first appeared on screen
+    toasts: Vec<(&'static str, Option<f64>)>,
+}
+
+impl Achievements {
+    pub fn new() -> Self {
+        Achievements {
+            unlocked: BTreeSet::new(),
+            total_kills: 0,
+            run_tags: BTreeSet::new(),
+            toasts: Vec::new(),
+        }
+    }
+
+    pub fn start_run(&mut self) {
+        self.run_tags.clear();
+    }
+
+    // Updates progress from one event and unlocks whatever it completed, returns true when something changed
+    // that needs saving
+    pub fn handle(&mut self, event: &GameEvent) -> bool {
+        match event {
+            GameEvent::EnemyKilled { .. } => self.total_kills += 1,
+            GameEvent::ItemPurchased { tags, .. } => self.run_tags.extend(tags.iter().cloned()),
+            _ => {}
+        }
+        let completed: Vec<&'static str> = ACHIEVEMENTS.iter()
+            .filter(|achievement| !self.unlocked.contains(achievement.id) && self.is_met(&achievement.condition, event))
+            .map(|achievement| achievement.id)
+            .collect();
+        for id in completed.iter() {
+            self.unlocked.insert(id);
+            self.toasts.push((id, None));
+        }
+        // Kills are saved with the next unlock or at the end of the run, not on every kill
+        !completed.is_empty()
+    }
+
+    fn is_met(&self, condition: &Condition, event: &GameEvent) -> bool {
+        match (condition, event) {
+            (Condition::TotalKills(kills), GameEvent::EnemyKilled { .. }) => self.total_kills >= *kills,
+            (Condition::KillKind(wanted), GameEvent::EnemyKilled { kind, .. }) => wanted == kind,
+            (Condition::ReachWave(target), GameEvent::WaveStarted { wave }) => wave >= target,
+            (Condition::ReachLevel(target), GameEvent::LevelUp { level }) => level >= target,
+            (Condition::BuyItem(wanted), GameEvent::ItemPurchased { name, .. }) => wanted == name,
+            (Condition::Win, GameEvent::RunWon) => true,
+            (Condition::WinWithoutTag(tag), GameEvent::RunWon) => !self.run_tags.contains(*tag),
+            _ => false,
+        }
+    }
+
+    pub fn is_unlocked(&self, id: &str) -> bool {
+        self.unlocked.contains(id)
+    }
+
+    // Small banners in the corner for recently unlocked achievements, they fade out on their own
+    pub fn draw_toasts(&mut self) {
+        let now = get_time();
+        for (_, shown_at) in self.toasts.iter_mut() {
+            shown_at.get_or_insert(now);
+        }
+        self.toasts.retain(|(_, shown_at)| now - shown_at.unwrap_or(now) < TOAST_DURATION);
+        for (i, (id, shown_at)) in self.toasts.iter().enumerate() {
+            let shown_at = shown_at.unwrap_or(now);
+            let Some(achievement) = ACHIEVEMENTS.iter().find(|achievement| achievement.id == *id) else {
+                continue;
+            };
+            let alpha = ((TOAST_DURATION - (now - shown_at)) as f32).min(1.0);
+            let position = Vec2::new(screen_width() - 340.0, screen_height() - 90.0 - i as f32 * 80.0);
+            draw_rectangle(position.x, position.y, 320.0, 70.0, Color::new(0.0, 0.0, 0.0, 0.8 * alpha));
+            draw_text("Achievement unlocked", position.x + 12.0, position.y + 25.0, 22.0, Color::new(1.0, 0.84, 0.0, alpha));
+            draw_text(achievement.name, position.x + 12.0, position.y + 55.0, 28.0, Color::new(1.0, 1.0, 1.0, alpha));
+        }
+    }
+
+    // Draws one frame of the achievements gallery, returns true when the player goes back
+    pub fn gallery(&self, background_texture: &Texture2D, nav: &mut Navigation) -> bool {
+        background::draw(background_texture);
+        draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.7));
+
+        let top = screen_height() / 2.0 - ACHIEVEMENTS.len() as f32 * ROW_HEIGHT / 2.0;
+        let left = screen_width() / 2.0 - 300.0;
+        draw_text("ACHIEVEMENTS", screen_width() / 2.0 - 150.0, top - 60.0, 50.0, WHITE);
+        draw_text(&format!("{}/{}", self.unlocked.len(), ACHIEVEMENTS.len()), screen_width() / 2.0 - 20.0, top - 20.0, 28.0, GOLD);
+
+        for (i, achievement) in ACHIEVEMENTS.iter().enumerate() {
+            let y = top + i as f32 * ROW_HEIGHT;
+            let (color, marker) = if self.is_unlocked(achievement.id) { (GOLD, "[x]") } else { (GRAY, "[ ]") };
+            draw_text(marker, left, y + 30.0, 28.0, color);
+            draw_text(achievement.name, left + 50.0, y + 30.0, 28.0, color);
+            draw_text(achievement.description, left + 50.0, y + 50.0, 20.0, LIGHTGRAY);
+        }
+
+        let mut back_btn = Button::new("BACK");
+        back_btn.set_position(Vec2::new(screen_width() / 2.0 - 100.0, top + ACHIEVEMENTS.len() as f32 * ROW_HEIGHT + 20.0));
+        back_btn.draw(nav)
+    }
+
+    // Reads the achievements file, a missing file means nothing is unlocked yet
+    pub fn load() -> Self {
+        let mut achievements = Achievements::new();
+        let Ok(contents) = fs::read_to_string(ACHIEVEMENTS_FILE) else {
+            return achievements;
+        };
+        for line in contents.lines() {
+            let Some((name, value)) = line.split_once('=') else {
+                continue;
+            };
+            match name.trim() {
+                "total_kills" => achievements.total_kills = value.trim().parse().unwrap_or(0),
+                "unlocked" => {
+                    if let Some(achievement) = ACHIEVEMENTS.iter().find(|achievement| achievement.id == value.trim()) {
+                        achievements.unlocked.insert(achievement.id);
+                    }
+                }
+                _ => {}
+            }
+        }
+        achievements
+    }
+
+    pub fn save(&self) {
+        let mut contents = format!("total_kills={}\n", self.total_kills);
+        for id in self.unlocked.iter() {
+            contents.push_str(&format!("unlocked={}\n", id));
+        }
+        // Failing to write achievements shouldn't stop the game
+        let _ = fs::write(ACHIEVEMENTS_FILE, contents);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bought(name: &str, tag: &str) -> GameEvent {
+        GameEvent::ItemPurchased { name: name.to_string(), tags: vec![tag.to_string()], price: 50 }
+    }
+
+    #[test]
+    fn reaching_a_wave_unlocks_once() {
+        let mut achievements = Achievements::new();
+        assert!(!achievements.handle(&GameEvent::WaveStarted { wave: 4 }));
+        assert!(achievements.handle(&GameEvent::WaveStarted { wave: 5 }));
+        assert!(achievements.is_unlocked("warming_up"));
+        assert!(!achievements.handle(&GameEvent::WaveStarted { wave: 6 }));
+    }
+
+    #[test]
+    fn buying_a_tagged_item_blocks_a_win_until_the_next_run() {
+        let mut achievements = Achievements::new();
+        achievements.handle(&bought("Iron Shield", "defense"));
+        achievements.handle(&GameEvent::RunWon);
+        assert!(achievements.is_unlocked("champion"));
+        assert!(!achievements.is_unlocked("glass_cannon"));
+
+        achievements.start_run();
+        achievements.handle(&bought("Speed Boots", "speed"));
+        achievements.handle(&GameEvent::RunWon);
+        assert!(achievements.is_unlocked("glass_cannon"));
+    }
+}
@@ -0,0 +1,13 @@
+use macroquad::audio::{play_sound, PlaySoundParams, Sound};
+use crate::events::GameEvent;
+use crate::settings;
+
+// The shoot sound is very loud, this is its level at full volume
+const SHOOT_VOLUME: f32 = 0.001;
+
+// Plays the sound effect for an event, if it has one
+pub fn handle_event(event: &GameEvent, shoot_sound: &Sound) {
+    if let GameEvent::Shot = event {
+        play_sound(shoot_sound, PlaySoundParams { looped: false, volume: SHOOT_VOLUME * settings::sfx_volume() });
+    }
+}
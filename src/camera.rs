@@ -2,6 +2,7 @@ use macroquad::camera::{set_camera, Camera2D};
 use macroquad::math::{vec2, Vec2};
 use macroquad::prelude::*;
 use crate::player::Player;
+use crate::settings;
 
 pub(crate) struct Camera {}
 
@@ -19,4 +20,20 @@ impl Camera {
         };
         set_camera(&camera);
     }
+
+    // Screen space camera for the HUD, zoomed by the UI scale setting and anchored to the top left
+    pub(crate) fn ui() {
+        let size = Camera::ui_size();
+        let camera = Camera2D {
+            target: size / 2.0,
+            zoom: vec2(2.0 / size.x, 2.0 / size.y),
+            ..Default::default()
+        };
+        set_camera(&camera);
+    }
+
+    // Size of the screen in HUD units
+    pub(crate) fn ui_size() -> Vec2 {
+        vec2(screen_width(), screen_height()) / settings::ui_scale()
+    }
 }
\ No newline at end of file
@@ -0,0 +1,59 @@
+use crate::player::{Player, DEFAULT_CHARACTER};
+use crate::shop::MAX_WEAPON_COUNT;
+
+// A playable character, applied on top of the base player at the start of a run
+pub(crate) struct Character {
+    pub name: &'static str,
+    pub description: &'static str,
+    // Tokens needed to unlock it, 0 means it is always available
+    pub cost: u32,
+    pub max_health: f32,
+    pub movement_speed_multiplier: f32,
+    pub weapon_count: i32,
+    pub weapon_damage: f32,
+}
+
+pub const CHARACTERS: [Character; 3] = [
+    Character {
+        name: DEFAULT_CHARACTER,
+        description: "No strengths, no weaknesses",
+        cost: 0,
+        max_health: 0.0,
+        movement_speed_multiplier: 1.0,
+        weapon_count: 0,
+        weapon_damage: 0.0,
+    },
+    Character {
+        name: "Gunner",
+        description: "One more weapon, less health",
+        cost: 150,
+        max_health: -3.0,
+        movement_speed_multiplier: 1.0,
+        weapon_count: 1,
+        weapon_damage: 0.0,
+    },
+    Character {
+        name: "Runner",
+        description: "25% faster, weaker weapons",
+        cost: 200,
+        max_health: 0.0,
+        movement_speed_multiplier: 1.25,
+        weapon_count: 0,
+        weapon_damage: -2.0,
+    },
+];
+
+impl Character {
+    pub fn find(name: &str) -> Option<&'static Character> {
+        CHARACTERS.iter().find(|character| character.name == name)
+    }
+
+    pub fn apply(&self, player: &mut Player) {
+        player.character = self.name.to_string();
+        player.stats.max_health += self.max_health;
+        player.stats.health = player.stats.health.min(player.stats.max_health);
+        player.stats.movement_speed *= self.movement_speed_multiplier;
+        player.weapon.count = (player.weapon.count as i32 + self.weapon_count).clamp(1, MAX_WEAPON_COUNT as i32) as usize;
+        player.weapon.damage += self.weapon_damage;
+    }
+}
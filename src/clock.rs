@@ -0,0 +1,46 @@
+use macroquad::prelude::*;
+
+const MIN_SCALE: f32 = 0.1;
+const MAX_SCALE: f32 = 4.0;
+
+// Gameplay time for a run. It only advances on frames where the game is actually played, so menus,
+// the shop and the pause overlay don't count, and it can run slower or faster than real time.
+pub(crate) struct GameClock {
+    time: f64,
+    delta: f32,
+    scale: f32,
+}
+
+impl GameClock {
+    pub fn new() -> Self {
+        GameClock {
+            time: 0.0,
+            delta: 0.0,
+            scale: 1.0,
+        }
+    }
+
+    // Advances the clock by the last frame, call once at the start of every played frame
+    pub fn tick(&mut self) {
+        self.delta = get_frame_time() * self.scale;
+        self.time += self.delta as f64;
+    }
+
+    // Seconds of gameplay since the run started
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    // Scaled seconds since the previous played frame
+    pub fn delta(&self) -> f32 {
+        self.delta
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = scale.clamp(MIN_SCALE, MAX_SCALE);
+    }
+}
@@ -0,0 +1,74 @@
+use macroquad::prelude::*;
+use crate::background;
+use crate::input::{self, Action};
+use crate::menu::Button;
+use crate::navigation::Navigation;
+use crate::settings;
+
+const ROW_HEIGHT: f32 = 60.0;
+
+// Lists every rebindable action, activating one waits for the next key press and binds it
+pub(crate) struct Controls {
+    waiting_for: Option<Action>,
+    message: String,
+}
+
+impl Controls {
+    pub fn new() -> Self {
+        Controls {
+            waiting_for: None,
+            message: String::new(),
+        }
+    }
+
+    // Draws one frame of the controls screen, returns true when the player goes back
+    pub fn draw(&mut self, background_texture: &Texture2D, nav: &mut Navigation) -> bool {
+        background::draw(background_texture);
+        draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.7));
+
+        let top = screen_height() / 2.0 - (input::REBINDABLE.len() as f32 * ROW_HEIGHT) / 2.0 - 60.0;
+        draw_text("CONTROLS", screen_width() / 2.0 - 90.0, top - 40.0, 50.0, WHITE);
+
+        // Buttons ignore the frame a key is captured on, otherwise Enter would start another rebind
+        let busy = self.waiting_for.is_some();
+        if let Some(action) = self.waiting_for {
+            if let Some(key) = input::bindable_key_pressed() {
+                self.message = match input::rebind(action, key) {
+                    Ok(Some(displaced)) => format!("{} was using {}, it is now bound to {}", displaced.name(), input::key_name(key), input::key_name(displaced.key())),
+                    Ok(None) => String::new(),
+                    Err(owner) => format!("{} is reserved for {}", input::key_name(key), owner.name()),
+                };
+                settings::save();
+                self.waiting_for = None;
+            }
+        }
+
+        for (i, action) in input::REBINDABLE.iter().enumerate() {
+            let y = top + i as f32 * ROW_HEIGHT;
+            let color = if input::has_conflict(*action) { RED } else { WHITE };
+            draw_text(action.name(), screen_width() / 2.0 - 250.0, y + 35.0, 30.0, color);
+
+            let text = if self.waiting_for == Some(*action) { "PRESS A KEY".to_string() } else { input::key_name(action.key()) };
+            let mut key_btn = Button::new(&text);
+            key_btn.set_position(Vec2::new(screen_width() / 2.0 + 50.0, y));
+            if key_btn.draw(nav) && !busy {
+                self.waiting_for = Some(*action);
+            }
+        }
+
+        let bottom = top + input::REBINDABLE.len() as f32 * ROW_HEIGHT + 20.0;
+        draw_text(&self.message, screen_width() / 2.0 - 250.0, bottom, 24.0, YELLOW);
+
+        let mut reset_btn = Button::new("DEFAULTS");
+        reset_btn.set_position(Vec2::new(screen_width() / 2.0 - 210.0, bottom + 30.0));
+        if reset_btn.draw(nav) && !busy {
+            input::reset_bindings();
+            settings::save();
+            self.message.clear();
+        }
+
+        let mut back_btn = Button::new("BACK");
+        back_btn.set_position(Vec2::new(screen_width() / 2.0 + 10.0, bottom + 30.0));
+        back_btn.draw(nav) && !busy
+    }
+}
@@ -1,5 +1,6 @@
 use macroquad::math::vec2;
 use macroquad::prelude::*;
+use crate::clock::GameClock;
 
 #[allow(dead_code)]
 pub(crate) fn debug(mut debug_x: f32, mut debug_y: f32) -> (f32, f32) {
@@ -61,4 +62,21 @@ pub(crate) fn debug(mut debug_x: f32, mut debug_y: f32) -> (f32, f32) {
         println!("X: {}, Y: {}", debug_x, debug_y);
     }
     (debug_x, debug_y)
-}
\ No newline at end of file
+}
+
+// Debug builds only: [ and ] slow the game down or speed it up, backslash goes back to normal speed.
+// None of them can be bound to an action, so they never fire together with a control.
+pub(crate) fn time_scale_keys(clock: &mut GameClock) {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+    if is_key_pressed(KeyCode::LeftBracket) {
+        clock.set_scale(clock.scale() / 2.0);
+    }
+    if is_key_pressed(KeyCode::RightBracket) {
+        clock.set_scale(clock.scale() * 2.0);
+    }
+    if is_key_pressed(KeyCode::Backslash) {
+        clock.set_scale(1.0);
+    }
+}
@@ -3,12 +3,16 @@ use macroquad::prelude::*;
 use crate::player::Player;
 use crate::sprite::Sprite;
 use crate::utilities;
+use crate::clock::GameClock;
+use crate::events::{self, GameEvent};
 
 #[derive(Clone)]
 pub(crate) struct Enemies {
     pub enemy_pool: Vec<Enemy>,
     pub last_enemy_spawn_time: f64,
     pub enemy_spawn_rate: f64,
+    // Scales health, damage and spawn rate of every enemy that spawns, grows in endless mode
+    pub difficulty: f32,
 }
 
 #[derive(Clone)]
@@ -60,7 +64,7 @@ impl Enemy {
         }
     }
 
-    pub fn update(enemies: &mut Vec<Enemy>, player: &mut Player) {
+    pub fn update(enemies: &mut Vec<Enemy>, player: &mut Player, clock: &GameClock) {
         let enemies_len = enemies.len();
 
         for i in 0..enemies_len {
@@ -75,13 +79,18 @@ impl Enemy {
             let rest_of_enemies: Vec<&Enemy> = rest_of_enemies.collect();
 
             // Call movement with the rest of the enemies
-            Enemy::movement(enemy, player, rest_of_enemies, 30.0);
-            Enemy::attack(enemy, player);
-            enemy.sprite.update();
+            Enemy::movement(enemy, player, rest_of_enemies, 30.0, clock.delta());
+            Enemy::attack(enemy, player, clock.time());
+            enemy.sprite.update(clock.delta());
             enemy.draw();
         }
     }
 
+    // Name used for this enemy in the run summary
+    pub fn kind(&self) -> &'static str {
+        if self.elite { "Elite" } else { "Normal" }
+    }
+
     pub  fn draw(&mut self) {
         if self.active {
             self.sprite.size = self.size;
@@ -89,7 +98,7 @@ impl Enemy {
         }
     }
 
-    pub fn movement(enemy: &mut Enemy, player: &Player, enemies: Vec<&Enemy>, separation_radius: f32) {
+    pub fn movement(enemy: &mut Enemy, player: &Player, enemies: Vec<&Enemy>, separation_radius: f32, dt: f32) {
         if enemy.active {
             let mut direction_to_player = (player.position - enemy.position).normalize();
 
@@ -116,7 +125,7 @@ impl Enemy {
             let final_direction = combined_direction.normalize();
 
             // Apply movement
-            enemy.position += final_direction * enemy.movement_speed * get_frame_time();
+            enemy.position += final_direction * enemy.movement_speed * dt;
         }
     }
 
@@ -126,10 +135,11 @@ impl Enemy {
         player_rect.overlaps(&enemy_rect)
     }
 
-    pub fn attack(&mut self, player: &mut Player) {
-        if Enemy::is_colliding(&self, &player) && get_time() - self.last_attack_time > self.attack_speed && player.stats.health > 0.0 && self.active {
+    pub fn attack(&mut self, player: &mut Player, current_time: f64) {
+        if Enemy::is_colliding(&self, &player) && current_time - self.last_attack_time > self.attack_speed && player.stats.health > 0.0 && self.active {
             player.stats.health -= self.damage;
-            self.last_attack_time = get_time();
+            events::emit(GameEvent::PlayerHit { damage: self.damage });
+            self.last_attack_time = current_time;
         }
     }
 
@@ -138,14 +148,12 @@ impl Enemy {
         if player.stats.level.level > 1 {
             spawn_rate = enemies.enemy_spawn_rate / (player.stats.level.level as f64);
         }
+        spawn_rate /= enemies.difficulty as f64;
         if current_type - enemies.last_enemy_spawn_time > spawn_rate {
             enemies.last_enemy_spawn_time = current_type;
-            for enemy in enemies.enemy_pool.iter_mut() {
-                if !enemy.active {
-                    enemy.active = true;
-                    enemy.position = Enemy::spawn_position(true);
-                    break;
-                }
+            let difficulty = enemies.difficulty;
+            if let Some(enemy) = enemies.enemy_pool.iter_mut().find(|enemy| !enemy.active) {
+                enemy.set_enemy_active(difficulty);
             }
         }
     }
@@ -199,13 +207,14 @@ impl Enemy {
         }
 
         for enemy in enemies.iter_mut() {
-            enemy.sprite.update();
+            enemy.sprite.update(0.0);
             enemy.draw();
         }
         Enemies {
             enemy_pool: enemies,
-            last_enemy_spawn_time: get_time(),
+            last_enemy_spawn_time: 0.0,
             enemy_spawn_rate: 2.0,
+            difficulty: 1.0,
         }
     }
 
@@ -217,9 +226,11 @@ impl Enemy {
         self.last_attack_time = 0.0;
     }
 
-    pub fn set_enemy_active(&mut self) {
+    pub fn set_enemy_active(&mut self, difficulty: f32) {
         self.active = true;
         self.position = Enemy::spawn_position(true);
+        self.health *= difficulty;
+        self.damage *= difficulty;
     }
 
     fn spawn_position(active: bool) -> Vec2 {
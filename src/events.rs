@@ -0,0 +1,34 @@
+use std::cell::RefCell;
+use macroquad::math::Vec2;
+
+// Things that happen during a run. Gameplay code only emits them, everything that reacts to them
+// (run stats, floating text, drops, wave bookkeeping, achievements, sound effects) handles them once
+// per frame after the frame's update, so adding a reaction doesn't mean touching combat code.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum GameEvent {
+    EnemyDamaged { position: Vec2, damage: f32, critical: bool },
+    EnemyKilled { kind: &'static str, elite: bool, position: Vec2, experience: f32 },
+    PlayerHit { damage: f32 },
+    // A weapon fired a bullet
+    Shot,
+    ItemPurchased { name: String, tags: Vec<String>, price: u32 },
+    WaveStarted { wave: u32 },
+    // The wave that just ended, the shop opens after it
+    WaveEnded { wave: u32 },
+    LevelUp { level: u32 },
+    // The final wave of a standard run was survived
+    RunWon,
+}
+
+thread_local! {
+    static QUEUE: RefCell<Vec<GameEvent>> = const { RefCell::new(Vec::new()) };
+}
+
+pub fn emit(event: GameEvent) {
+    QUEUE.with(|queue| queue.borrow_mut().push(event));
+}
+
+// Takes every event emitted since the last call, oldest first
+pub fn drain() -> Vec<GameEvent> {
+    QUEUE.with(|queue| std::mem::take(&mut *queue.borrow_mut()))
+}
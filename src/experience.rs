@@ -0,0 +1,68 @@
+use macroquad::prelude::*;
+use crate::player::Player;
+use crate::pickups::{self, Mergeable};
+
+// Once more orbs than this are on the ground nearby ones start merging together
+const MERGE_THRESHOLD: usize = 60;
+const MERGE_RADIUS: f32 = 40.0;
+
+pub(crate) struct Experience {
+    pub position: Vec2,
+    pub size: f32,
+    pub value: f32,
+    pub collected: bool,
+}
+
+impl Experience {
+    pub fn new(position: Vec2, value: f32) -> Self {
+        Experience {
+            position,
+            size: Experience::size_for_value(value),
+            value,
+            collected: false,
+        }
+    }
+
+    // Orbs grow with the experience they hold, capped so merged orbs don't cover the map
+    fn size_for_value(value: f32) -> f32 {
+        (4.0 + value.sqrt() * 2.0).min(24.0)
+    }
+
+    pub fn draw(&self) {
+        draw_poly(self.position.x, self.position.y, 4, self.size, 45.0, DARKGREEN);
+        draw_poly(self.position.x, self.position.y, 4, self.size * 0.6, 45.0, GREEN);
+    }
+
+    pub fn update(orbs: &mut Vec<Experience>, player: &mut Player, attraction_radius: f32, dt: f32) {
+        if orbs.len() > MERGE_THRESHOLD {
+            pickups::merge_nearby(orbs, MERGE_RADIUS);
+        }
+
+        for orb in orbs.iter_mut() {
+            player.collect_experience(orb, attraction_radius, dt);
+
+            if !orb.collected {
+                orb.draw();
+            }
+        }
+
+        // Remove collected orbs
+        orbs.retain(|orb| !orb.collected);
+    }
+
+}
+
+impl Mergeable for Experience {
+    fn position(&self) -> Vec2 {
+        self.position
+    }
+
+    fn can_merge(&self) -> bool {
+        !self.collected
+    }
+
+    fn absorb(&mut self, other: &Experience) {
+        self.value += other.value;
+        self.size = Experience::size_for_value(self.value);
+    }
+}
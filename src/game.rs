@@ -0,0 +1,304 @@
+use macroquad::audio::Sound;
+use macroquad::prelude::*;
+use crate::achievements::Achievements;
+use crate::audio;
+use crate::background;
+use crate::camera::Camera;
+use crate::controls::Controls;
+use crate::debugging;
+use crate::events;
+use crate::high_scores::HighScores;
+use crate::history;
+use crate::enemy::{Enemy, EnemyTextures};
+use crate::input::{self, Action};
+use crate::menu::{Menu, MenuChoice};
+use crate::navigation::Navigation;
+use crate::pause::{self, PauseChoice};
+use crate::profile;
+use crate::player::Attack;
+use crate::settings_menu;
+use crate::upgrades_menu;
+use crate::shop::{Item, ShopTextures};
+use crate::waves::{RunOutcome, VictoryChoice, WaveEvent, Waves};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum GameState {
+    Menu,
+    Controls,
+    Settings,
+    HighScores,
+    Upgrades,
+    Achievements,
+    Playing,
+    Paused,
+    // Closing the shop between waves starts the next wave, opened mid-wave it just goes back to the game
+    Shop { between_waves: bool },
+    // A single pickup can cross several level thresholds at once
+    LevelUp { levels: u32 },
+    Victory,
+    GameOver,
+}
+
+impl GameState {
+    // Overlays are drawn on top of the frozen game scene
+    fn is_overlay(&self) -> bool {
+        matches!(self, GameState::Paused | GameState::LevelUp { .. } | GameState::Victory | GameState::GameOver)
+    }
+}
+
+enum Transition {
+    Push(GameState),
+    Pop,
+    // Pops the top state and pushes another in its place
+    Replace(GameState),
+    // Clears the whole stack, used to start a new run or go back to the main menu
+    Reset(GameState),
+}
+
+pub(crate) struct Assets {
+    pub enemy_textures: Vec<EnemyTextures>,
+    pub shop_textures: ShopTextures,
+    pub background: Texture2D,
+    pub bullet: Texture2D,
+    pub shoot_sound: Sound,
+}
+
+// Top level state stack. Only the state on top is updated each frame, the ones below stay frozen.
+pub(crate) struct Game {
+    states: Vec<GameState>,
+    assets: Assets,
+    menu: Menu,
+    run: Option<Waves>,
+    nav: Navigation,
+    controls: Controls,
+    high_scores: HighScores,
+    achievements: Achievements,
+}
+
+impl Game {
+    pub fn new(assets: Assets, menu: Menu) -> Self {
+        Game {
+            states: vec![GameState::Menu],
+            assets,
+            menu,
+            run: None,
+            nav: Navigation::new(),
+            controls: Controls::new(),
+            high_scores: HighScores::new(),
+            achievements: Achievements::load(),
+        }
+    }
+
+    // Updates and draws the state on top of the stack, call once per frame
+    pub async fn frame(&mut self) {
+        let state = *self.states.last().unwrap_or(&GameState::Menu);
+        self.nav.begin_frame();
+        if state.is_overlay() {
+            if let Some(run) = self.run.as_mut() {
+                run.draw_scene(&self.assets.background);
+            }
+        }
+
+        let transition = match state {
+            GameState::Menu => self.main_menu(),
+            GameState::Controls => self.controls.draw(&self.menu.background, &mut self.nav).then_some(Transition::Pop),
+            GameState::HighScores => self.high_scores.draw(&self.menu.background, &mut self.nav).then_some(Transition::Pop),
+            GameState::Upgrades => upgrades_menu::upgrades_screen(&self.menu.background, &mut self.nav).then_some(Transition::Pop),
+            GameState::Achievements => self.achievements.gallery(&self.menu.background, &mut self.nav).then_some(Transition::Pop),
+            GameState::Settings => settings_menu::settings_screen(&self.menu.background, &mut self.nav).then_some(Transition::Pop),
+            GameState::Playing => self.play().await,
+            GameState::Paused => self.paused(),
+            GameState::Shop { .. } => self.shop(),
+            GameState::LevelUp { levels } => self.level_up(levels),
+            GameState::Victory => self.victory(),
+            GameState::GameOver => self.game_over(),
+        };
+
+        let mut unlocked = false;
+        for event in events::drain() {
+            if let Some(run) = self.run.as_mut() {
+                run.handle_event(&event);
+            }
+            audio::handle_event(&event, &self.assets.shoot_sound);
+            unlocked |= self.achievements.handle(&event);
+        }
+        if unlocked {
+            self.achievements.save();
+        }
+        self.achievements.draw_toasts();
+
+        if let Some(transition) = transition {
+            self.apply(transition).await;
+        }
+    }
+
+    async fn apply(&mut self, transition: Transition) {
+        match transition {
+            Transition::Push(state) => {
+                self.enter(state).await;
+                self.states.push(state);
+            }
+            Transition::Pop => {
+                if let Some(state) = self.states.pop() {
+                    self.exit(state);
+                }
+            }
+            Transition::Replace(state) => {
+                if let Some(old) = self.states.pop() {
+                    self.exit(old);
+                }
+                self.enter(state).await;
+                self.states.push(state);
+            }
+            Transition::Reset(state) => {
+                while let Some(old) = self.states.pop() {
+                    self.exit(old);
+                }
+                self.enter(state).await;
+                self.states.push(state);
+            }
+        }
+        // Every screen starts with the focus on its first widget
+        self.nav = Navigation::new();
+    }
+
+    // Runs when a state is pushed, not when it becomes the top again after a pop
+    async fn enter(&mut self, state: GameState) {
+        match state {
+            GameState::Playing => {
+                self.run = Some(Waves::new(self.assets.enemy_textures.clone(), self.assets.shop_textures.clone()).await);
+                self.achievements.start_run();
+            }
+            GameState::Controls => self.controls = Controls::new(),
+            GameState::HighScores => self.high_scores = HighScores::new(),
+            GameState::GameOver => self.finish_run(),
+            _ => {}
+        }
+    }
+
+    // Every run that ends is written to the history and pays out tokens, retrying or quitting afterwards doesn't matter
+    fn finish_run(&mut self) {
+        if let Some(run) = self.run.as_ref() {
+            history::append(&run.record());
+            let tokens = run.tokens();
+            profile::modify(|profile| profile.tokens += tokens);
+            self.achievements.save();
+        }
+    }
+
+    fn exit(&mut self, state: GameState) {
+        match state {
+            // Every way out of a run passes here, kills since the last unlock are only saved with the run
+            GameState::Playing => {
+                self.achievements.save();
+                self.run = None;
+            }
+            GameState::Shop { between_waves: true } => {
+                if let Some(run) = self.run.as_mut() {
+                    run.start_next_wave();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn main_menu(&mut self) -> Option<Transition> {
+        match self.menu.menu(&mut self.nav)? {
+            MenuChoice::Start => Some(Transition::Reset(GameState::Playing)),
+            MenuChoice::Controls => Some(Transition::Push(GameState::Controls)),
+            MenuChoice::Settings => Some(Transition::Push(GameState::Settings)),
+            MenuChoice::HighScores => Some(Transition::Push(GameState::HighScores)),
+            MenuChoice::Upgrades => Some(Transition::Push(GameState::Upgrades)),
+            MenuChoice::Achievements => Some(Transition::Push(GameState::Achievements)),
+        }
+    }
+
+    async fn play(&mut self) -> Option<Transition> {
+        let run = self.run.as_mut()?;
+        let level_before = run.player.stats.level.level;
+        run.clock.tick();
+        debugging::time_scale_keys(&mut run.clock);
+
+        let event = run.update();
+
+        Camera::init(&run.player);
+
+        background::draw(&self.assets.background);
+
+        run.player.movement(run.clock.delta()).await;
+        run.player.sprite.update(run.clock.delta());
+        run.player.draw();
+
+        run.pickups.update(&mut run.player, &run.shop.shop_textures, run.clock.delta());
+
+        Attack::attack(&run.clock, &mut run.player, &mut run.bullets, &mut run.enemies.enemy_pool);
+        Enemy::enemy_collection(run.clock.time(), &mut run.enemies, &run.player).await;
+        Attack::draw_weapon_system(&mut run.enemies.enemy_pool, &mut run.player, &mut run.bullets, &mut self.assets.bullet.clone());
+
+        Enemy::update(&mut run.enemies.enemy_pool, &mut run.player, &run.clock);
+
+        run.damage_text.update(run.clock.delta());
+        run.damage_text.draw();
+
+        Camera::ui();
+
+        run.draw_game_status();
+        run.player.draw_stats();
+
+        set_default_camera();
+
+        match event {
+            Some(WaveEvent::PlayerDied) => Some(Transition::Push(GameState::GameOver)),
+            Some(WaveEvent::Cleared) => Some(Transition::Push(GameState::Shop { between_waves: true })),
+            Some(WaveEvent::Won) => Some(Transition::Push(GameState::Victory)),
+            None if input::action_pressed(Action::Pause) => Some(Transition::Push(GameState::Paused)),
+            None if input::action_pressed(Action::OpenShop) => Some(Transition::Push(GameState::Shop { between_waves: false })),
+            None if run.player.stats.level.level > level_before => {
+                Some(Transition::Push(GameState::LevelUp { levels: run.player.stats.level.level - level_before }))
+            }
+            None => None,
+        }
+    }
+
+    fn paused(&mut self) -> Option<Transition> {
+        match pause::pause_menu(&mut self.nav)? {
+            PauseChoice::Resume => Some(Transition::Pop),
+            PauseChoice::Settings => Some(Transition::Push(GameState::Settings)),
+            PauseChoice::Restart => Some(Transition::Reset(GameState::Playing)),
+            PauseChoice::MainMenu => Some(Transition::Reset(GameState::Menu)),
+        }
+    }
+
+    fn shop(&mut self) -> Option<Transition> {
+        let run = self.run.as_mut()?;
+        let done = Item::shop_menu(&mut run.shop.shop_items, &mut run.shop.locked_slots, &mut run.player, &run.shop.shop_textures, &mut run.shop.shop, run.waves.wave, &mut self.nav);
+        done.then_some(Transition::Pop)
+    }
+
+    fn level_up(&mut self, levels: u32) -> Option<Transition> {
+        let run = self.run.as_ref()?;
+        run.player.draw_level_up(levels, &mut self.nav).then_some(Transition::Pop)
+    }
+
+    fn victory(&mut self) -> Option<Transition> {
+        let run = self.run.as_mut()?;
+        match run.victory(&mut self.nav)? {
+            VictoryChoice::Endless => {
+                run.start_endless();
+                Some(Transition::Replace(GameState::Shop { between_waves: true }))
+            }
+            VictoryChoice::MainMenu => {
+                self.finish_run();
+                Some(Transition::Reset(GameState::Menu))
+            }
+        }
+    }
+
+    fn game_over(&mut self) -> Option<Transition> {
+        let run = self.run.as_mut()?;
+        match run.game_over(&mut self.nav)? {
+            RunOutcome::Retry => Some(Transition::Reset(GameState::Playing)),
+            RunOutcome::MainMenu => Some(Transition::Reset(GameState::Menu)),
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use macroquad::prelude::*;
+use crate::background;
+use crate::history::{self, RunMode, RunRecord, SortBy};
+use crate::menu::Button;
+use crate::navigation::Navigation;
+
+const ROW_HEIGHT: f32 = 36.0;
+const SHOWN_RUNS: usize = 10;
+
+// Best runs from the local history, sortable by score or by wave reached.
+// Standard and endless runs are listed separately.
+pub(crate) struct HighScores {
+    records: Vec<RunRecord>,
+    sort_by: SortBy,
+    mode: RunMode,
+}
+
+impl HighScores {
+    // Reads the history file, so a new one is built each time the screen opens
+    pub fn new() -> Self {
+        let mut high_scores = HighScores {
+            records: history::load(),
+            sort_by: SortBy::Score,
+            mode: RunMode::Standard,
+        };
+        history::sort(&mut high_scores.records, high_scores.sort_by);
+        high_scores
+    }
+
+    // Draws one frame of the high scores screen, returns true when the player goes back
+    pub fn draw(&mut self, background_texture: &Texture2D, nav: &mut Navigation) -> bool {
+        background::draw(background_texture);
+        draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.7));
+
+        let top = screen_height() / 2.0 - (SHOWN_RUNS as f32 * ROW_HEIGHT) / 2.0 - 40.0;
+        let left = screen_width() / 2.0 - 520.0;
+        draw_text("HIGH SCORES", screen_width() / 2.0 - 130.0, top - 60.0, 50.0, WHITE);
+
+        let columns = [("#", 0.0), ("Score", 50.0), ("Wave", 180.0), ("Character", 270.0), ("Date", 430.0), ("Build", 590.0)];
+        for (title, x) in columns {
+            draw_text(title, left + x, top, 28.0, GOLD);
+        }
+
+        let mut records = self.records.iter().filter(|record| record.mode == self.mode).peekable();
+        if records.peek().is_none() {
+            draw_text("No runs yet", left, top + ROW_HEIGHT * 1.5, 28.0, GRAY);
+        }
+        for (i, record) in records.take(SHOWN_RUNS).enumerate() {
+            let y = top + ROW_HEIGHT * (i as f32 + 1.5);
+            draw_text(&(i + 1).to_string(), left, y, 26.0, WHITE);
+            draw_text(&record.score.to_string(), left + 50.0, y, 26.0, WHITE);
+            draw_text(&record.wave.to_string(), left + 180.0, y, 26.0, WHITE);
+            draw_text(&record.character, left + 270.0, y, 26.0, WHITE);
+            draw_text(&record.date, left + 430.0, y, 26.0, WHITE);
+            draw_text(&truncate(&record.build, 40), left + 590.0, y, 22.0, LIGHTGRAY);
+        }
+
+        let bottom = top + ROW_HEIGHT * (SHOWN_RUNS as f32 + 1.5);
+        let sort_text = match self.sort_by {
+            SortBy::Score => "BY SCORE",
+            SortBy::Wave => "BY WAVE",
+        };
+        let mut mode_btn = Button::new(&self.mode.name().to_uppercase());
+        mode_btn.set_position(Vec2::new(screen_width() / 2.0 - 320.0, bottom));
+        if mode_btn.draw(nav) {
+            self.mode = match self.mode {
+                RunMode::Standard => RunMode::Endless,
+                RunMode::Endless => RunMode::Standard,
+            };
+        }
+
+        let mut sort_btn = Button::new(sort_text);
+        sort_btn.set_position(Vec2::new(screen_width() / 2.0 - 100.0, bottom));
+        if sort_btn.draw(nav) {
+            self.sort_by = match self.sort_by {
+                SortBy::Score => SortBy::Wave,
+                SortBy::Wave => SortBy::Score,
+            };
+            history::sort(&mut self.records, self.sort_by);
+        }
+
+        let mut back_btn = Button::new("BACK");
+        back_btn.set_position(Vec2::new(screen_width() / 2.0 + 120.0, bottom));
+        back_btn.draw(nav)
+    }
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        text.to_string()
+    } else {
+        format!("{}...", text.chars().take(max_chars).collect::<String>())
+    }
+}
@@ -0,0 +1,131 @@
+use std::cmp::Reverse;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+
+const HISTORY_FILE: &str = "history.txt";
+
+// One finished run, stored as a tab separated line in the history file
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct RunRecord {
+    pub date: String,
+    pub seed: u64,
+    pub character: String,
+    pub wave: u32,
+    pub score: u32,
+    // Item names with their counts, e.g. "Boots x2, Scope"
+    pub build: String,
+    pub mode: RunMode,
+}
+
+// Endless runs keep going after the final wave so they are ranked on their own
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum RunMode {
+    Standard,
+    Endless,
+}
+
+impl RunMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RunMode::Standard => "Standard",
+            RunMode::Endless => "Endless",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<RunMode> {
+        [RunMode::Standard, RunMode::Endless].into_iter().find(|mode| mode.name() == name)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum SortBy {
+    Score,
+    Wave,
+}
+
+impl RunRecord {
+    fn to_line(&self) -> String {
+        format!("{}\t{}\t{}\t{}\t{}\t{}\t{}", self.date, self.seed, self.character, self.wave, self.score, self.build, self.mode.name())
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let mut fields = line.split('\t');
+        Some(RunRecord {
+            date: fields.next()?.to_string(),
+            seed: fields.next()?.parse().ok()?,
+            character: fields.next()?.to_string(),
+            wave: fields.next()?.parse().ok()?,
+            score: fields.next()?.parse().ok()?,
+            build: fields.next().unwrap_or("").to_string(),
+            // Runs recorded before endless mode existed have no mode
+            mode: fields.next().and_then(RunMode::from_name).unwrap_or(RunMode::Standard),
+        })
+    }
+}
+
+// Every recorded run, oldest first. Lines that can't be read are skipped.
+pub fn load() -> Vec<RunRecord> {
+    let Ok(contents) = fs::read_to_string(HISTORY_FILE) else {
+        return Vec::new();
+    };
+    contents.lines().filter_map(RunRecord::from_line).collect()
+}
+
+pub fn append(record: &RunRecord) {
+    // Failing to write the history shouldn't stop the game
+    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(HISTORY_FILE) {
+        let _ = writeln!(file, "{}", record.to_line());
+    }
+}
+
+// Best runs first, the other field breaks ties
+pub fn sort(records: &mut [RunRecord], sort_by: SortBy) {
+    match sort_by {
+        SortBy::Score => records.sort_by_key(|record| Reverse((record.score, record.wave))),
+        SortBy::Wave => records.sort_by_key(|record| Reverse((record.wave, record.score))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(wave: u32, score: u32) -> RunRecord {
+        RunRecord {
+            date: "2024-05-01".to_string(),
+            seed: 42,
+            character: "Survivor".to_string(),
+            wave,
+            score,
+            build: "Boots x2, Scope".to_string(),
+            mode: RunMode::Endless,
+        }
+    }
+
+    #[test]
+    fn record_round_trips_through_a_line() {
+        let original = record(7, 7350);
+        assert_eq!(RunRecord::from_line(&original.to_line()), Some(original));
+    }
+
+    #[test]
+    fn lines_without_a_mode_are_standard_runs() {
+        let record = RunRecord::from_line("2024-05-01\t42\tSurvivor\t7\t7350\tScope").unwrap();
+        assert_eq!(record.mode, RunMode::Standard);
+        assert_eq!(record.build, "Scope");
+    }
+
+    #[test]
+    fn malformed_lines_are_skipped() {
+        assert_eq!(RunRecord::from_line("2024-05-01\tnot a seed"), None);
+    }
+
+    #[test]
+    fn sorting_by_wave_and_by_score() {
+        let mut records = vec![record(3, 9000), record(5, 5000), record(5, 6000)];
+        sort(&mut records, SortBy::Wave);
+        assert_eq!(records.iter().map(|r| r.score).collect::<Vec<_>>(), vec![6000, 5000, 9000]);
+        sort(&mut records, SortBy::Score);
+        assert_eq!(records.iter().map(|r| r.score).collect::<Vec<_>>(), vec![9000, 6000, 5000]);
+    }
+}
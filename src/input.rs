@@ -0,0 +1,308 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use macroquad::prelude::*;
+
+// Gamepads need gilrs, which links against libudev on Linux, so they are an opt-in feature
+#[cfg(feature = "gamepad")]
+mod gamepad;
+
+// Keyboard only builds, no gamepad is ever connected
+#[cfg(not(feature = "gamepad"))]
+mod gamepad {
+    use macroquad::math::Vec2;
+    use super::Action;
+
+    pub fn poll() {}
+
+    pub fn action_down(_action: Action) -> bool {
+        false
+    }
+
+    pub fn action_pressed(_action: Action) -> bool {
+        false
+    }
+
+    pub fn left_stick() -> Vec2 {
+        Vec2::ZERO
+    }
+
+    pub fn notice() -> Option<(String, f64)> {
+        None
+    }
+}
+
+// Sticks report small values at rest, anything inside this radius is treated as centered
+const STICK_DEADZONE: f32 = 0.2;
+
+// Everything gameplay and menus can ask for, independent of the device that produced it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Dash,
+    Pause,
+    OpenShop,
+    MenuUp,
+    MenuDown,
+    MenuLeft,
+    MenuRight,
+    Confirm,
+    Secondary,
+}
+
+// Actions the player can rebind on the controls screen, menu navigation keys stay fixed
+pub const REBINDABLE: [Action; 8] = [
+    Action::MoveUp,
+    Action::MoveDown,
+    Action::MoveLeft,
+    Action::MoveRight,
+    Action::Dash,
+    Action::Pause,
+    Action::OpenShop,
+    Action::Secondary,
+];
+
+// Menu navigation that always keeps its default key, rebindable actions can't take these keys
+const FIXED: [Action; 5] = [Action::MenuUp, Action::MenuDown, Action::MenuLeft, Action::MenuRight, Action::Confirm];
+
+// Keys that can be bound and stored in the settings file. [, ] and \ are left out, debug builds use them
+// for the time scale.
+const BINDABLE_KEYS: [KeyCode; 66] = [
+    KeyCode::A, KeyCode::B, KeyCode::C, KeyCode::D, KeyCode::E, KeyCode::F, KeyCode::G, KeyCode::H,
+    KeyCode::I, KeyCode::J, KeyCode::K, KeyCode::L, KeyCode::M, KeyCode::N, KeyCode::O, KeyCode::P,
+    KeyCode::Q, KeyCode::R, KeyCode::S, KeyCode::T, KeyCode::U, KeyCode::V, KeyCode::W, KeyCode::X,
+    KeyCode::Y, KeyCode::Z,
+    KeyCode::Key0, KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4,
+    KeyCode::Key5, KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9,
+    KeyCode::F1, KeyCode::F2, KeyCode::F3, KeyCode::F4, KeyCode::F5, KeyCode::F6,
+    KeyCode::F7, KeyCode::F8, KeyCode::F9, KeyCode::F10, KeyCode::F11, KeyCode::F12,
+    KeyCode::Up, KeyCode::Down, KeyCode::Left, KeyCode::Right,
+    KeyCode::Space, KeyCode::Enter, KeyCode::Escape, KeyCode::Tab, KeyCode::Backspace,
+    KeyCode::LeftShift, KeyCode::RightShift, KeyCode::LeftControl, KeyCode::RightControl,
+    KeyCode::LeftAlt, KeyCode::RightAlt, KeyCode::Comma, KeyCode::Period, KeyCode::Slash,
+];
+
+impl Action {
+    fn default_key(&self) -> KeyCode {
+        match self {
+            Action::MoveUp => KeyCode::W,
+            Action::MoveDown => KeyCode::S,
+            Action::MoveLeft => KeyCode::A,
+            Action::MoveRight => KeyCode::D,
+            Action::Dash => KeyCode::Space,
+            Action::Pause => KeyCode::Escape,
+            Action::OpenShop => KeyCode::F1,
+            Action::MenuUp => KeyCode::Up,
+            Action::MenuDown => KeyCode::Down,
+            Action::MenuLeft => KeyCode::Left,
+            Action::MenuRight => KeyCode::Right,
+            Action::Confirm => KeyCode::Enter,
+            Action::Secondary => KeyCode::L,
+        }
+    }
+
+    // The key currently bound to this action
+    pub fn key(&self) -> KeyCode {
+        BINDINGS.with(|bindings| bindings.borrow().get(self).copied().unwrap_or(self.default_key()))
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::MoveUp => "Move Up",
+            Action::MoveDown => "Move Down",
+            Action::MoveLeft => "Move Left",
+            Action::MoveRight => "Move Right",
+            Action::Dash => "Dash",
+            Action::Pause => "Pause",
+            Action::OpenShop => "Open Shop",
+            Action::MenuUp => "Menu Up",
+            Action::MenuDown => "Menu Down",
+            Action::MenuLeft => "Menu Left",
+            Action::MenuRight => "Menu Right",
+            Action::Confirm => "Confirm",
+            Action::Secondary => "Lock Item",
+        }
+    }
+
+    // Name used for the action in the settings file
+    pub fn id(&self) -> &'static str {
+        match self {
+            Action::MoveUp => "move_up",
+            Action::MoveDown => "move_down",
+            Action::MoveLeft => "move_left",
+            Action::MoveRight => "move_right",
+            Action::Dash => "dash",
+            Action::Pause => "pause",
+            Action::OpenShop => "open_shop",
+            Action::MenuUp => "menu_up",
+            Action::MenuDown => "menu_down",
+            Action::MenuLeft => "menu_left",
+            Action::MenuRight => "menu_right",
+            Action::Confirm => "confirm",
+            Action::Secondary => "secondary",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Action> {
+        REBINDABLE.iter().copied().find(|action| action.id() == id)
+    }
+}
+
+thread_local! {
+    // Only actions that were rebound are stored, everything else uses its default key
+    static BINDINGS: RefCell<HashMap<Action, KeyCode>> = RefCell::new(HashMap::new());
+}
+
+// Drains pending gamepad events, call once per frame before reading any input
+pub fn poll() {
+    gamepad::poll();
+}
+
+pub fn action_down(action: Action) -> bool {
+    is_key_down(action.key()) || gamepad::action_down(action)
+}
+
+pub fn action_pressed(action: Action) -> bool {
+    is_key_pressed(action.key()) || gamepad::action_pressed(action)
+}
+
+pub fn set_binding(action: Action, key: KeyCode) {
+    BINDINGS.with(|bindings| bindings.borrow_mut().insert(action, key));
+}
+
+// Binds the key to the action. If another action already used that key the two swap keys,
+// the displaced action is returned so the controls screen can tell the player. Keys of the fixed
+// menu actions are refused, the error is the action that owns the key.
+pub fn rebind(action: Action, key: KeyCode) -> Result<Option<Action>, Action> {
+    if let Some(owner) = FIXED.iter().copied().find(|fixed| fixed.key() == key) {
+        return Err(owner);
+    }
+    let previous = action.key();
+    let displaced = REBINDABLE.iter().copied().find(|other| *other != action && other.key() == key);
+    if let Some(other) = displaced {
+        set_binding(other, previous);
+    }
+    set_binding(action, key);
+    Ok(displaced)
+}
+
+pub fn reset_bindings() {
+    BINDINGS.with(|bindings| bindings.borrow_mut().clear());
+}
+
+// True when another action shares this action's key, a settings file edited by hand can still bind a menu key
+pub fn has_conflict(action: Action) -> bool {
+    REBINDABLE.iter().chain(FIXED.iter()).any(|other| *other != action && other.key() == action.key())
+}
+
+pub fn key_name(key: KeyCode) -> String {
+    format!("{:?}", key)
+}
+
+pub fn key_from_name(name: &str) -> Option<KeyCode> {
+    BINDABLE_KEYS.iter().copied().find(|key| key_name(*key) == name)
+}
+
+// The bindable key pressed this frame, used while waiting for a new binding
+pub fn bindable_key_pressed() -> Option<KeyCode> {
+    BINDABLE_KEYS.iter().copied().find(|key| is_key_pressed(*key))
+}
+
+// Movement direction with a length of at most 1, the stick allows walking slower than full speed
+pub fn movement() -> Vec2 {
+    let mut direction = Vec2::ZERO;
+    if action_down(Action::MoveRight) {
+        direction.x += 1.0;
+    }
+    if action_down(Action::MoveLeft) {
+        direction.x -= 1.0;
+    }
+    if action_down(Action::MoveUp) {
+        direction.y -= 1.0;
+    }
+    if action_down(Action::MoveDown) {
+        direction.y += 1.0;
+    }
+
+    direction += apply_deadzone(gamepad::left_stick());
+    if direction.length() > 1.0 {
+        direction = direction.normalize();
+    }
+    direction
+}
+
+// Radial deadzone, rescaled so the stick still reaches full speed at the edge
+fn apply_deadzone(stick: Vec2) -> Vec2 {
+    let length = stick.length();
+    if length <= STICK_DEADZONE {
+        return Vec2::ZERO;
+    }
+    let scaled = ((length - STICK_DEADZONE) / (1.0 - STICK_DEADZONE)).min(1.0);
+    stick / length * scaled
+}
+
+// Shows a short notice in the corner when a gamepad is plugged in or removed
+pub fn draw_gamepad_notice() {
+    if let Some((message, expires)) = gamepad::notice() {
+        if get_time() < expires {
+            let text_measurements = measure_text(&message, None, 24, 1.0);
+            let x = screen_width() - text_measurements.width - 30.0;
+            let y = screen_height() - 50.0;
+            draw_rectangle(x - 10.0, y - 28.0, text_measurements.width + 20.0, 40.0, Color::new(0.0, 0.0, 0.0, 0.7));
+            draw_text(&message, x, y, 24.0, WHITE);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deadzone_ignores_small_stick_values() {
+        assert_eq!(apply_deadzone(Vec2::new(0.1, -0.1)), Vec2::ZERO);
+    }
+
+    #[test]
+    fn deadzone_rescales_to_full_range() {
+        let full = apply_deadzone(Vec2::new(1.0, 0.0));
+        assert!((full.x - 1.0).abs() < 0.001);
+        let half = apply_deadzone(Vec2::new(0.0, 0.6));
+        assert!((half.y - 0.5).abs() < 0.001);
+    }
+
+    #[test]
+    fn rebinding_a_used_key_swaps_the_bindings() {
+        reset_bindings();
+        assert_eq!(rebind(Action::Dash, KeyCode::W), Ok(Some(Action::MoveUp)));
+        assert_eq!(Action::Dash.key(), KeyCode::W);
+        assert_eq!(Action::MoveUp.key(), KeyCode::Space);
+        assert!(!has_conflict(Action::Dash));
+    }
+
+    #[test]
+    fn menu_keys_are_refused_and_flagged() {
+        reset_bindings();
+        assert_eq!(rebind(Action::Pause, KeyCode::Enter), Err(Action::Confirm));
+        assert_eq!(Action::Pause.key(), KeyCode::Escape);
+        set_binding(Action::MoveUp, KeyCode::Up);
+        assert!(has_conflict(Action::MoveUp));
+        reset_bindings();
+    }
+
+    #[test]
+    fn time_scale_keys_cannot_be_bound() {
+        for key in [KeyCode::LeftBracket, KeyCode::RightBracket, KeyCode::Backslash] {
+            assert!(!BINDABLE_KEYS.contains(&key));
+        }
+    }
+
+    #[test]
+    fn key_names_round_trip() {
+        for key in BINDABLE_KEYS {
+            assert_eq!(key_from_name(&key_name(key)), Some(key));
+        }
+    }
+}
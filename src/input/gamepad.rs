@@ -0,0 +1,106 @@
+use std::cell::RefCell;
+use gilrs::{Axis, Button, EventType, Gilrs};
+use macroquad::prelude::*;
+use super::{Action, STICK_DEADZONE};
+
+// Seconds a gamepad connected/disconnected notice stays on screen
+const NOTICE_DURATION: f64 = 3.0;
+
+// Gamepad state read through free functions, the same way macroquad exposes keyboard and mouse
+struct GamepadState {
+    // None when no gamepad backend is available on this platform
+    gilrs: Option<Gilrs>,
+    pressed: Vec<Button>,
+    notice: Option<(String, f64)>,
+}
+
+thread_local! {
+    static GAMEPAD: RefCell<GamepadState> = RefCell::new(GamepadState {
+        gilrs: Gilrs::new().ok(),
+        pressed: Vec::new(),
+        notice: None,
+    });
+}
+
+fn buttons(action: Action) -> &'static [Button] {
+    match action {
+        Action::MoveUp => &[Button::DPadUp],
+        Action::MoveDown => &[Button::DPadDown],
+        Action::MoveLeft => &[Button::DPadLeft],
+        Action::MoveRight => &[Button::DPadRight],
+        Action::Dash => &[Button::South, Button::RightTrigger],
+        Action::Pause => &[Button::Start],
+        Action::OpenShop => &[Button::Select],
+        Action::MenuUp => &[Button::DPadUp],
+        Action::MenuDown => &[Button::DPadDown],
+        Action::MenuLeft => &[Button::DPadLeft],
+        Action::MenuRight => &[Button::DPadRight],
+        Action::Confirm => &[Button::South],
+        Action::Secondary => &[Button::West],
+    }
+}
+
+pub fn poll() {
+    GAMEPAD.with(|state| {
+        let mut state = state.borrow_mut();
+        let mut pressed = Vec::new();
+        let mut notice = None;
+        if let Some(gilrs) = state.gilrs.as_mut() {
+            while let Some(event) = gilrs.next_event() {
+                match event.event {
+                    EventType::ButtonPressed(button, _) => pressed.push(button),
+                    // Gamepads can be plugged in or pulled out at any point during a run
+                    EventType::Connected => {
+                        notice = Some(format!("{} connected", gilrs.gamepad(event.id).name()));
+                    }
+                    EventType::Disconnected => {
+                        notice = Some(format!("{} disconnected", gilrs.gamepad(event.id).name()));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        state.pressed = pressed;
+        if let Some(message) = notice {
+            state.notice = Some((message, get_time() + NOTICE_DURATION));
+        }
+    });
+}
+
+pub fn action_down(action: Action) -> bool {
+    GAMEPAD.with(|state| {
+        let state = state.borrow();
+        match state.gilrs.as_ref() {
+            Some(gilrs) => gilrs.gamepads().any(|(_, gamepad)| buttons(action).iter().any(|button| gamepad.is_pressed(*button))),
+            None => false,
+        }
+    })
+}
+
+// True only on the frame one of the action's buttons went down on any connected gamepad
+pub fn action_pressed(action: Action) -> bool {
+    GAMEPAD.with(|state| {
+        let state = state.borrow();
+        buttons(action).iter().any(|button| state.pressed.contains(button))
+    })
+}
+
+// Raw left stick of the first gamepad that is being pushed, before the deadzone is rescaled
+pub fn left_stick() -> Vec2 {
+    GAMEPAD.with(|state| {
+        let state = state.borrow();
+        let Some(gilrs) = state.gilrs.as_ref() else {
+            return Vec2::ZERO;
+        };
+        gilrs.gamepads()
+            // The stick's Y axis points up, the screen's points down
+            .map(|(_, gamepad)| Vec2::new(gamepad.value(Axis::LeftStickX), -gamepad.value(Axis::LeftStickY)))
+            .find(|stick| stick.length() > STICK_DEADZONE)
+            .unwrap_or(Vec2::ZERO)
+    })
+}
+
+// The connected/disconnected message and when it stops being shown
+pub fn notice() -> Option<(String, f64)> {
+    GAMEPAD.with(|state| state.borrow().notice.clone())
+}
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+use crate::shop::Item;
+
+pub struct InventoryEntry {
+    pub item: Item,
+    pub count: u32,
+    // How much of the item's quantity each copy actually applied, capped stats can take less than the item says
+    applied: Vec<u32>,
+}
+
+// Every permanent item the player has bought, grouped by name
+pub struct Inventory {
+    pub entries: Vec<InventoryEntry>,
+    // How many tiers of each tag's synergy are currently applied to the player's stats
+    synergy_tiers: HashMap<String, usize>,
+}
+
+impl Inventory {
+    pub fn new() -> Self {
+        Inventory {
+            entries: Vec::new(),
+            synergy_tiers: HashMap::new(),
+        }
+    }
+
+    pub fn add(&mut self, item: &Item, applied: u32) {
+        match self.entries.iter_mut().find(|entry| entry.item.name == item.name) {
+            Some(entry) => {
+                entry.count += 1;
+                entry.applied.push(applied);
+            }
+            None => self.entries.push(InventoryEntry { item: item.clone(), count: 1, applied: vec![applied] }),
+        }
+    }
+
+    // Removes the copy bought last and returns how much it applied, None when the item isn't owned
+    pub fn remove(&mut self, name: &str) -> Option<u32> {
+        let index = self.entries.iter().position(|entry| entry.item.name == name)?;
+        let entry = &mut self.entries[index];
+        entry.count -= 1;
+        let applied = entry.applied.pop().unwrap_or(entry.item.attributes.quantity);
+        if entry.count == 0 {
+            self.entries.remove(index);
+        }
+        Some(applied)
+    }
+
+    pub fn active_synergy_tiers(&self, tag: &str) -> usize {
+        self.synergy_tiers.get(tag).copied().unwrap_or(0)
+    }
+
+    pub fn set_active_synergy_tiers(&mut self, tag: &str, tiers: usize) {
+        self.synergy_tiers.insert(tag.to_string(), tiers);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shop::{Attributes, Rarity};
+
+    fn weapon_item() -> Item {
+        Item {
+            texture_name: "love_potion".to_string(),
+            name: "Extra Weapon Slot".to_string(),
+            price: 100,
+            rarity: Rarity::Rare,
+            description: String::new(),
+            level_requirement: 1,
+            tags: Vec::new(),
+            attributes: Attributes { attribute_type: "weapon_count".to_string(), quantity: 1, duration: 0 },
+        }
+    }
+
+    #[test]
+    fn removing_returns_what_the_last_copy_applied() {
+        let mut inventory = Inventory::new();
+        inventory.add(&weapon_item(), 1);
+        inventory.add(&weapon_item(), 0);
+        assert_eq!(inventory.remove("Extra Weapon Slot"), Some(0));
+        assert_eq!(inventory.remove("Extra Weapon Slot"), Some(1));
+        assert_eq!(inventory.remove("Extra Weapon Slot"), None);
+        assert!(inventory.entries.is_empty());
+    }
+}
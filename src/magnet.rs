@@ -0,0 +1,46 @@
+use macroquad::prelude::*;
+use crate::player::Player;
+
+// How long a collected magnet pulls every pickup on the map towards the player
+pub const MAGNET_DURATION: f32 = 5.0;
+
+pub(crate) struct Magnet {
+    pub position: Vec2,
+    pub size: f32,
+    pub collected: bool,
+}
+
+impl Magnet {
+    pub fn new(position: Vec2) -> Self {
+        Magnet {
+            position,
+            size: 12.0,
+            collected: false,
+        }
+    }
+
+    pub fn draw(&self) {
+        draw_circle(self.position.x, self.position.y, self.size, RED);
+        draw_circle(self.position.x, self.position.y, self.size * 0.5, WHITE);
+        draw_rectangle(self.position.x - self.size, self.position.y, self.size * 2.0, self.size, RED);
+        draw_rectangle(self.position.x - self.size, self.position.y + self.size * 0.5, self.size * 0.5, self.size * 0.5, LIGHTGRAY);
+        draw_rectangle(self.position.x + self.size * 0.5, self.position.y + self.size * 0.5, self.size * 0.5, self.size * 0.5, LIGHTGRAY);
+    }
+
+    // Returns true when a magnet was picked up this frame
+    pub fn update(magnets: &mut Vec<Magnet>, player: &Player, dt: f32) -> bool {
+        let mut picked_up = false;
+        for magnet in magnets.iter_mut() {
+            if player.attract(&mut magnet.position, player.stats.pickup_radius, dt) {
+                magnet.collected = true;
+                picked_up = true;
+            } else {
+                magnet.draw();
+            }
+        }
+
+        // Remove collected magnets
+        magnets.retain(|magnet| !magnet.collected);
+        picked_up
+    }
+}
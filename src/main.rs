@@ -6,28 +6,59 @@ mod sprite;
 mod camera;
 mod menu;
 mod money;
+mod experience;
+mod magnet;
+mod pickups;
+mod inventory;
+mod run_stats;
+mod history;
+mod high_scores;
+mod characters;
+mod profile;
+mod events;
+mod achievements;
+mod audio;
+mod input;
+mod navigation;
+mod controls;
+mod settings;
+mod settings_menu;
+mod upgrades_menu;
+mod pause;
+mod clock;
+mod game;
 mod debugging;
 mod shop;
 mod waves;
 
 use macroquad::prelude::*;
 use macroquad::audio::*;
-use player::Player;
-use player::Attack;
-use player::DamageText;
 use enemy::Enemy;
-use camera::Camera;
 use menu::Menu;
-use money::Money;
-use shop::Item;
-use shop::Shop;
-use waves::{Waves, ShopDetails};
+use game::{Assets, Game};
 use crate::shop::textures;
 
 
-#[macroquad::main("Kill Carl!")]
+// Settings are read before the window opens so it starts in the right mode, vsync can only be set here
+fn window_conf() -> Conf {
+    settings::load();
+    let settings = settings::current();
+    let (width, height) = settings::RESOLUTIONS[settings.resolution];
+    Conf {
+        window_title: "Kill Carl!".to_string(),
+        fullscreen: settings.fullscreen,
+        window_width: width as i32,
+        window_height: height as i32,
+        platform: miniquad::conf::Platform {
+            swap_interval: Some(if settings.vsync { 1 } else { 0 }),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+#[macroquad::main(window_conf)]
 async fn main() {
-    set_fullscreen(true);
 
     /*################## Textures ###############*/
     let enemy_textures = Enemy::enemy_textures().await;
@@ -41,62 +72,24 @@ async fn main() {
     /*###########################################*/
 
     /*################## Loading Screen ###############*/
-    let mut menu = Menu::new().await;
-    menu.menu().await;
+    let menu = Menu::new().await;
     /*###########################################*/
 
-    let mut shop = Shop::new();
-    let mut player = Player::new().await;
-    let mut enemies = Enemy::spawn_enemy_pool(enemy_textures).await;
-    let mut shop_items = Item::shop(player.stats.level.level);
-    let mut shop_details = ShopDetails {
-        shop_items,
+    let assets = Assets {
+        enemy_textures,
         shop_textures,
-        shop,
+        background: background_texture,
+        bullet: bullet_texture,
+        shoot_sound,
     };
-    let mut waves = Waves::new(1, 20.0, &mut player, &mut enemies, &mut menu, &mut shop_details);
-
-    let mut bullets: Vec<Attack> = Vec::new();
-    let mut coins: Vec<Money> = vec![];
-    let mut damage_text = DamageText::new();
-
+    profile::load();
+    let mut game = Game::new(assets, menu);
 
     loop {
-        if is_key_down(KeyCode::Escape) {
-            waves.menu.start_btn.text = "RESUME".to_string();
-            waves.menu.menu().await;
-        }
-
-        if is_key_down(KeyCode::F1) {
-            waves.menu.start_btn.text = "RESUME".to_string();
-            Item::shop_menu(&mut waves.shop.shop_items, &mut waves.player, &waves.shop.shop_textures, &mut waves.shop.shop).await;
-        }
-
-        waves.update().await;
-
-        Camera::init(&waves.player);
-
-        background::draw(&background_texture);
-
-        waves.player.movement().await;
-        waves.player.sprite.update();
-        waves.player.draw();
-
-        Money::update(&mut coins, &mut waves.player, waves.shop.shop_textures.get_texture("money_notes").unwrap().clone());
-
-        Attack::attack(get_time(), &mut waves.player, &mut bullets, &mut waves.enemies.enemy_pool, &mut coins, &mut damage_text);
-        Enemy::enemy_collection(get_time(), &mut waves.enemies, &waves.player).await;
-        Attack::draw_weapon_system(&mut waves.enemies.enemy_pool, &mut waves.player, &mut bullets, &mut bullet_texture.clone(), &shoot_sound);
-
-        Enemy::update(&mut waves.enemies.enemy_pool, &mut waves.player);
-
-        damage_text.update(get_frame_time());
-        damage_text.draw();
-
-        set_default_camera();
-
-        waves.draw_game_status();
-        waves.player.draw_stats();
+        input::poll();
+        game.frame().await;
+        input::draw_gamepad_notice();
+        settings::limit_frame_rate();
         next_frame().await;
     }
-}
\ No newline at end of file
+}
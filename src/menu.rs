@@ -1,13 +1,28 @@
 use macroquad::prelude::*;
 use crate::background;
+use crate::navigation::{self, Navigation};
 use crate::utilities;
 
 pub(crate) struct Menu {
     pub start_btn: Button,
+    pub controls_btn: Button,
+    pub settings_btn: Button,
+    pub high_scores_btn: Button,
+    pub upgrades_btn: Button,
+    pub achievements_btn: Button,
     pub quit_btn: Button,
     pub background: Texture2D,
 }
 
+pub(crate) enum MenuChoice {
+    Start,
+    Controls,
+    Settings,
+    HighScores,
+    Upgrades,
+    Achievements,
+}
+
 pub struct Button {
     pos: Vec2,
     width: f32,
@@ -26,60 +41,112 @@ impl Menu {
             border_radius: 20.0,
         };
 
-        let quit_btn = Button {
+        let controls_btn = Button {
             pos: Vec2::new( screen_width() / 2.0 - 100.0, (screen_height() / 2.0 - 25.0) + 60.0),
             width: 200.0,
             height: 50.0,
+            text: "CONTROLS".to_string(),
+            border_radius: 20.0,
+        };
+
+        let settings_btn = Button {
+            pos: Vec2::new( screen_width() / 2.0 - 100.0, (screen_height() / 2.0 - 25.0) + 120.0),
+            width: 200.0,
+            height: 50.0,
+            text: "SETTINGS".to_string(),
+            border_radius: 20.0,
+        };
+
+        let high_scores_btn = Button {
+            pos: Vec2::new( screen_width() / 2.0 - 100.0, (screen_height() / 2.0 - 25.0) + 180.0),
+            width: 200.0,
+            height: 50.0,
+            text: "HIGH SCORES".to_string(),
+            border_radius: 20.0,
+        };
+
+        let upgrades_btn = Button {
+            pos: Vec2::new( screen_width() / 2.0 - 100.0, (screen_height() / 2.0 - 25.0) + 240.0),
+            width: 200.0,
+            height: 50.0,
+            text: "UPGRADES".to_string(),
+            border_radius: 20.0,
+        };
+
+        let achievements_btn = Button {
+            pos: Vec2::new( screen_width() / 2.0 - 100.0, (screen_height() / 2.0 - 25.0) + 300.0),
+            width: 200.0,
+            height: 50.0,
+            text: "ACHIEVEMENTS".to_string(),
+            border_radius: 20.0,
+        };
+
+        let quit_btn = Button {
+            pos: Vec2::new( screen_width() / 2.0 - 100.0, (screen_height() / 2.0 - 25.0) + 360.0),
+            width: 200.0,
+            height: 50.0,
             text: "QUIT".to_string(),
             border_radius: 20.0,
         };
 
         Menu {
             start_btn,
+            controls_btn,
+            settings_btn,
+            high_scores_btn,
+            upgrades_btn,
+            achievements_btn,
             quit_btn,
             background: load_texture("assets/background/background.png").await.unwrap(),
         }
     }
 
-    pub async fn menu(&mut self) {
-        loop {
-            background::draw(&self.background);
-            // Draw the semi-transparent background
-            let background_color = Color::new(0.0, 0.0, 0.0, 0.7);
-            draw_rectangle(0.0, 0.0, screen_width(), screen_height(), background_color);
-            if self.start() {
-                break;
-            }
-            next_frame().await;
-        }
+    pub fn menu(&mut self, nav: &mut Navigation) -> Option<MenuChoice> {
+        background::draw(&self.background);
+        // Draw the semi-transparent background
+        let background_color = Color::new(0.0, 0.0, 0.0, 0.7);
+        draw_rectangle(0.0, 0.0, screen_width(), screen_height(), background_color);
+        self.start(nav)
     }
 
-    pub(crate) fn start(&mut self) -> bool {
-        self.start_btn.pos = Vec2::new( screen_width() / 2.0 - 100.0, (screen_height() / 2.0) - (self.start_btn.height / 2.0));
-        self.quit_btn.pos = Vec2::new( screen_width() / 2.0 - 100.0, (screen_height() / 2.0) + 35.0);
-        let is_hovering_start = utilities::hovering_over(self.start_btn.pos, Vec2::new(self.start_btn.width, self.start_btn.height));
-        let start_text = utilities::center_text(&self.start_btn.text, 30, Vec2::new(self.start_btn.width, self.start_btn.height), self.start_btn.pos);
-        let btn_start_color = if is_hovering_start { GRAY } else { BLUE };
-        Menu::draw_rounded_rect(self.start_btn.pos.x, self.start_btn.pos.y, self.start_btn.width, self.start_btn.height, self.start_btn.border_radius, btn_start_color);
-        draw_text(&self.start_btn.text, start_text.x, start_text.y, 30.0, WHITE);
-
-        let is_hover_quit = utilities::hovering_over(self.quit_btn.pos, Vec2::new(self.quit_btn.width, self.quit_btn.height));
-        let quit_text = utilities::center_text("QUIT", 30, Vec2::new(self.quit_btn.width, self.quit_btn.height), self.quit_btn.pos);
-        let btn_quit_color = if is_hover_quit { GRAY } else { BLUE };
-        Menu::draw_rounded_rect(self.quit_btn.pos.x, self.quit_btn.pos.y, self.quit_btn.width, self.quit_btn.height, self.quit_btn.border_radius, btn_quit_color);
-        draw_text("QUIT", quit_text.x, quit_text.y, 30.0, WHITE);
-
-        if is_hovering_start && is_mouse_button_pressed(MouseButton::Left) {
-            true
-        } else if is_hover_quit && is_mouse_button_pressed(MouseButton::Left) {
+    pub(crate) fn start(&mut self, nav: &mut Navigation) -> Option<MenuChoice> {
+        // The column of buttons is moved up so all of them fit on a 720p screen
+        self.start_btn.pos = Vec2::new( screen_width() / 2.0 - 100.0, (screen_height() / 2.0) - 200.0);
+        self.controls_btn.pos = Vec2::new( screen_width() / 2.0 - 100.0, (screen_height() / 2.0) - 140.0);
+        self.settings_btn.pos = Vec2::new( screen_width() / 2.0 - 100.0, (screen_height() / 2.0) - 80.0);
+        self.high_scores_btn.pos = Vec2::new( screen_width() / 2.0 - 100.0, (screen_height() / 2.0) - 20.0);
+        self.upgrades_btn.pos = Vec2::new( screen_width() / 2.0 - 100.0, (screen_height() / 2.0) + 40.0);
+        self.achievements_btn.pos = Vec2::new( screen_width() / 2.0 - 100.0, (screen_height() / 2.0) + 100.0);
+        self.quit_btn.pos = Vec2::new( screen_width() / 2.0 - 100.0, (screen_height() / 2.0) + 160.0);
+        let start = self.start_btn.draw(nav);
+        let controls = self.controls_btn.draw(nav);
+        let settings = self.settings_btn.draw(nav);
+        let high_scores = self.high_scores_btn.draw(nav);
+        let upgrades = self.upgrades_btn.draw(nav);
+        let achievements = self.achievements_btn.draw(nav);
+        let quit = self.quit_btn.draw(nav);
+
+        if start {
+            Some(MenuChoice::Start)
+        } else if controls {
+            Some(MenuChoice::Controls)
+        } else if settings {
+            Some(MenuChoice::Settings)
+        } else if high_scores {
+            Some(MenuChoice::HighScores)
+        } else if upgrades {
+            Some(MenuChoice::Upgrades)
+        } else if achievements {
+            Some(MenuChoice::Achievements)
+        } else if quit {
             std::process::exit(0);
         } else {
-            false
+            None
         }
     }
 
     // Function to draw a rectangle with rounded corners
-    fn draw_rounded_rect(x: f32, y: f32, width: f32, height: f32, radius: f32, color: Color) {
+    pub(crate) fn draw_rounded_rect(x: f32, y: f32, width: f32, height: f32, radius: f32, color: Color) {
         // Draw the four rounded corners as circles
         draw_circle(x + radius, y + radius, radius, color);
         draw_circle(x + width - radius, y + radius, radius, color);
@@ -92,4 +159,41 @@ impl Menu {
         draw_rectangle(x + width - radius, y + radius, radius, height - 2.0 * radius, color);
         draw_rectangle(x + radius, y + height - radius, width - 2.0 * radius, radius, color);
     }
-}
\ No newline at end of file
+}
+
+impl Button {
+    pub fn new(text: &str) -> Self {
+        Button {
+            pos: Vec2::ZERO,
+            width: 200.0,
+            height: 50.0,
+            text: text.to_string(),
+            border_radius: 20.0,
+        }
+    }
+
+    pub fn set_position(&mut self, pos: Vec2) {
+        self.pos = pos;
+    }
+
+    pub fn set_size(&mut self, size: Vec2) {
+        self.width = size.x;
+        self.height = size.y;
+        self.border_radius = self.border_radius.min(size.y / 2.0);
+    }
+
+    // Draws the button and returns true when it was clicked or activated with the keyboard/gamepad
+    pub fn draw(&self, nav: &mut Navigation) -> bool {
+        let size = Vec2::new(self.width, self.height);
+        let is_hovered = utilities::hovering_over(self.pos, size);
+        let (focused, activated) = nav.widget(is_hovered);
+        let text_pos = utilities::center_text(&self.text, 30, size, self.pos);
+        let color = if is_hovered || focused { GRAY } else { BLUE };
+        Menu::draw_rounded_rect(self.pos.x, self.pos.y, self.width, self.height, self.border_radius, color);
+        if focused {
+            navigation::draw_focus(self.pos, size);
+        }
+        draw_text(&self.text, text_pos.x, text_pos.y, 30.0, WHITE);
+        activated
+    }
+}
@@ -1,26 +1,62 @@
 use macroquad::prelude::*;
 use crate::player::Player;
+use crate::shop::ShopTextures;
+use crate::pickups::{self, Mergeable};
+
+// Seconds an uncollected coin stays on the ground, it starts blinking near the end
+const COIN_LIFETIME: f32 = 20.0;
+const BAG_LIFETIME: f32 = 40.0;
+const BLINK_TIME: f32 = 5.0;
+
+// Once more coins than this are on the ground nearby ones start merging together
+const MERGE_THRESHOLD: usize = 40;
+const MERGE_RADIUS: f32 = 40.0;
 
 pub(crate) struct Money {
     pub position: Vec2,
     pub size: Vec2,
     pub value: u32,
     pub collected: bool,
+    pub time_left: f32,
+    pub bag: bool,
 }
 
 impl Money {
-    pub async fn new(position: Vec2, value: u32) -> Self {
+    pub fn new(position: Vec2, value: u32) -> Self {
         Money {
             position,
             size: vec2(20.0, 20.0),
             value,
             collected: false,
+            time_left: COIN_LIFETIME,
+            bag: false,
+        }
+    }
+
+    pub fn bag(position: Vec2, value: u32) -> Self {
+        Money {
+            position,
+            size: vec2(40.0, 40.0),
+            value,
+            collected: false,
+            time_left: BAG_LIFETIME,
+            bag: true,
         }
     }
 
-    pub fn draw(&self, money_texture: Texture2D) {
+    // Merged coins grow with the value they hold
+    fn size_for_value(value: u32) -> Vec2 {
+        let size = (20.0 + (value as f32).sqrt() * 2.0).min(36.0);
+        vec2(size, size)
+    }
+
+    pub fn draw(&self, money_texture: &Texture2D) {
+        // Blink faster the closer the coin is to despawning
+        if self.time_left < BLINK_TIME && (self.time_left * 8.0) as i32 % 2 == 0 {
+            return;
+        }
         draw_texture_ex(
-            &money_texture,
+            money_texture,
             self.position.x - self.size.x * 0.5,
             self.position.y - self.size.y * 0.5,
             WHITE,
@@ -31,17 +67,43 @@ impl Money {
         );
     }
 
-    pub fn update(coins: &mut Vec<Money>, player: &mut Player, money_texture: Texture2D) {
+    pub fn update(coins: &mut Vec<Money>, player: &mut Player, textures: &ShopTextures, attraction_radius: f32, dt: f32) {
+        if coins.len() > MERGE_THRESHOLD {
+            pickups::merge_nearby(coins, MERGE_RADIUS);
+        }
+
+        let money_texture = textures.get_texture("money_notes").unwrap();
+        let bag_texture = textures.get_texture("money_bag").unwrap();
+
         // Update and draw coins
         for coin in &mut coins.iter_mut() {
-            player.collect_coin(coin, 400.0); // Attraction radius of 100
+            player.collect_coin(coin, attraction_radius, dt);
+            coin.time_left -= dt;
 
             if !coin.collected {
-                coin.draw(money_texture.clone());
+                coin.draw(if coin.bag { bag_texture } else { money_texture });
             }
         }
 
-        // Remove collected coins
-        coins.retain(|coin| !coin.collected);
+        // Remove collected and expired coins
+        coins.retain(|coin| !coin.collected && coin.time_left > 0.0);
+    }
+
+}
+
+impl Mergeable for Money {
+    fn position(&self) -> Vec2 {
+        self.position
+    }
+
+    // Bags keep their own look and value
+    fn can_merge(&self) -> bool {
+        !self.collected && !self.bag
     }
-}
\ No newline at end of file
+
+    fn absorb(&mut self, other: &Money) {
+        self.value += other.value;
+        self.time_left = self.time_left.max(other.time_left);
+        self.size = Money::size_for_value(self.value);
+    }
+}
@@ -0,0 +1,71 @@
+use macroquad::prelude::*;
+use crate::input::{self, Action};
+
+// Focus-based navigation so menus can be driven without a mouse.
+// Widgets register themselves in draw order every frame, arrow keys or the D-pad move the focus
+// between them and Enter or the A button activates the focused one.
+pub(crate) struct Navigation {
+    focused: usize,
+    count: usize,
+    next_id: usize,
+    confirm: bool,
+    mouse_moved: bool,
+    last_mouse: Vec2,
+}
+
+impl Navigation {
+    pub fn new() -> Self {
+        Navigation {
+            focused: 0,
+            count: 0,
+            next_id: 0,
+            confirm: false,
+            mouse_moved: false,
+            last_mouse: Vec2::ZERO,
+        }
+    }
+
+    // Call at the start of every frame before any widget is drawn, after input::poll
+    pub fn begin_frame(&mut self) {
+        self.count = self.next_id;
+        self.next_id = 0;
+
+        if self.count > 0 {
+            if input::action_pressed(Action::MenuDown) || input::action_pressed(Action::MenuRight) {
+                self.focused = (self.focused + 1) % self.count;
+            }
+            if input::action_pressed(Action::MenuUp) || input::action_pressed(Action::MenuLeft) {
+                self.focused = (self.focused + self.count - 1) % self.count;
+            }
+            // Widgets can disappear between frames, e.g. a bought shop card
+            self.focused = self.focused.min(self.count - 1);
+        }
+        self.confirm = input::action_pressed(Action::Confirm);
+
+        let mouse: Vec2 = mouse_position().into();
+        self.mouse_moved = mouse != self.last_mouse;
+        self.last_mouse = mouse;
+    }
+
+    // Registers the next widget, returns whether it has focus and whether it was activated this frame
+    pub fn widget(&mut self, hovered: bool) -> (bool, bool) {
+        let id = self.next_id;
+        self.next_id += 1;
+        // Moving the mouse over a widget takes the focus with it
+        if hovered && self.mouse_moved {
+            self.focused = id;
+        }
+        let focused = self.focused == id;
+        let activated = (focused && self.confirm) || (hovered && is_mouse_button_pressed(MouseButton::Left));
+        (focused, activated)
+    }
+
+    // True on the frame the secondary action (L or the X button) is used on the focused widget
+    pub fn secondary(&self) -> bool {
+        input::action_pressed(Action::Secondary)
+    }
+}
+
+pub(crate) fn draw_focus(position: Vec2, size: Vec2) {
+    draw_rectangle_lines(position.x - 4.0, position.y - 4.0, size.x + 8.0, size.y + 8.0, 4.0, YELLOW);
+}
@@ -0,0 +1,39 @@
+use macroquad::prelude::*;
+use crate::input::{self, Action};
+use crate::menu::Button;
+use crate::navigation::Navigation;
+
+pub(crate) enum PauseChoice {
+    Resume,
+    Settings,
+    Restart,
+    MainMenu,
+}
+
+// Draws the pause overlay on top of the frozen game scene
+pub(crate) fn pause_menu(nav: &mut Navigation) -> Option<PauseChoice> {
+    if input::action_pressed(Action::Pause) {
+        return Some(PauseChoice::Resume);
+    }
+
+    draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.7));
+
+    let text_measurements = measure_text("PAUSED", None, 80, 1.0);
+    draw_text("PAUSED", screen_width() / 2.0 - text_measurements.width / 2.0, screen_height() / 2.0 - 120.0, 80.0, WHITE);
+
+    let choices = [
+        ("RESUME", PauseChoice::Resume),
+        ("SETTINGS", PauseChoice::Settings),
+        ("RESTART", PauseChoice::Restart),
+        ("MAIN MENU", PauseChoice::MainMenu),
+    ];
+    let mut chosen = None;
+    for (i, (text, choice)) in choices.into_iter().enumerate() {
+        let mut button = Button::new(text);
+        button.set_position(Vec2::new(screen_width() / 2.0 - 100.0, screen_height() / 2.0 - 60.0 + i as f32 * 60.0));
+        if button.draw(nav) {
+            chosen = Some(choice);
+        }
+    }
+    chosen
+}
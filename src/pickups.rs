@@ -0,0 +1,121 @@
+use macroquad::prelude::*;
+use crate::experience::Experience;
+use crate::magnet::{Magnet, MAGNET_DURATION};
+use crate::money::Money;
+use crate::player::Player;
+use crate::shop::ShopTextures;
+use crate::events::GameEvent;
+use crate::utilities;
+
+// Everything enemies leave behind on the ground for the player to collect
+pub(crate) struct Pickups {
+    pub coins: Vec<Money>,
+    pub orbs: Vec<Experience>,
+    pub magnets: Vec<Magnet>,
+    pub magnet_time_left: f32,
+}
+
+impl Pickups {
+    pub fn new() -> Self {
+        Pickups {
+            coins: vec![],
+            orbs: vec![],
+            magnets: vec![],
+            magnet_time_left: 0.0,
+        }
+    }
+
+    // Killed enemies always leave experience and sometimes a magnet, elites are more generous
+    pub fn handle_event(&mut self, event: &GameEvent) {
+        let GameEvent::EnemyKilled { elite, position, experience, .. } = event else {
+            return;
+        };
+        self.orbs.push(Experience::new(*position, *experience));
+        let magnet_chance = if *elite { 0.1 } else { 0.005 };
+        if utilities::random_chance(magnet_chance) {
+            self.magnets.push(Magnet::new(*position));
+        }
+        let bag_chance = if *elite { 0.2 } else { 0.01 };
+        if utilities::random_chance(bag_chance) {
+            self.coins.push(Money::bag(*position, utilities::random_number(50..=100) as u32));
+        } else {
+            self.coins.push(Money::new(*position, utilities::random_number(1..=10) as u32));
+        }
+    }
+
+    // Collects every coin still on the ground at the given rate, returns the money it paid out
+    pub fn harvest_coins(&mut self, rate: f32) -> u32 {
+        let total: u32 = self.coins.iter().map(|coin| coin.value).sum();
+        self.coins.clear();
+        (total as f32 * rate) as u32
+    }
+
+    pub fn update(&mut self, player: &mut Player, textures: &ShopTextures, dt: f32) {
+        if Magnet::update(&mut self.magnets, player, dt) {
+            self.magnet_time_left = MAGNET_DURATION;
+        }
+        self.magnet_time_left = (self.magnet_time_left - dt).max(0.0);
+
+        // While a magnet is active everything on screen is pulled in
+        let radius = if self.magnet_time_left > 0.0 { f32::MAX } else { player.stats.pickup_radius };
+        Money::update(&mut self.coins, player, textures, radius, dt);
+        Experience::update(&mut self.orbs, player, radius, dt);
+    }
+}
+
+// Something on the ground that can be combined with nearby pickups of the same kind
+pub(crate) trait Mergeable {
+    fn position(&self) -> Vec2;
+    fn can_merge(&self) -> bool;
+    // Adds the other pickup's value to this one, the other is removed afterwards
+    fn absorb(&mut self, other: &Self);
+}
+
+// Folds every pickup within the radius of an earlier one into it, so a crowded floor stays cheap to update
+pub fn merge_nearby<T: Mergeable>(pickups: &mut Vec<T>, radius: f32) {
+    let mut merged = vec![false; pickups.len()];
+    for i in 0..pickups.len() {
+        if merged[i] || !pickups[i].can_merge() {
+            continue;
+        }
+        for j in (i + 1)..pickups.len() {
+            if !merged[j] && pickups[j].can_merge() && pickups[i].position().distance(pickups[j].position()) < radius {
+                let (before, after) = pickups.split_at_mut(j);
+                before[i].absorb(&after[0]);
+                merged[j] = true;
+            }
+        }
+    }
+
+    // Merged pickups are dropped without paying out
+    let mut index = 0;
+    pickups.retain(|_| {
+        index += 1;
+        !merged[index - 1]
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearby_orbs_merge_and_keep_their_total_value() {
+        let mut orbs = vec![
+            Experience::new(vec2(0.0, 0.0), 2.0),
+            Experience::new(vec2(10.0, 0.0), 3.0),
+            Experience::new(vec2(500.0, 0.0), 4.0),
+        ];
+        merge_nearby(&mut orbs, 40.0);
+        assert_eq!(orbs.len(), 2);
+        assert_eq!(orbs[0].value, 5.0);
+        assert_eq!(orbs[1].value, 4.0);
+    }
+
+    #[test]
+    fn bags_never_merge() {
+        let mut coins = vec![Money::new(vec2(0.0, 0.0), 5), Money::bag(vec2(5.0, 0.0), 60)];
+        merge_nearby(&mut coins, 40.0);
+        assert_eq!(coins.len(), 2);
+    }
+}
@@ -1,10 +1,25 @@
-use macroquad::audio::{Sound, play_sound, PlaySoundParams };
 use macroquad::math::Vec2;
 use macroquad::prelude::*;
 use crate::sprite::Sprite;
 use crate::Enemy;
 use crate::money::Money;
+use crate::experience::Experience;
+use crate::events::{self, GameEvent};
+use crate::inventory::Inventory;
+use crate::run_stats::RunStats;
+use crate::input::{self, Action};
 use crate::utilities;
+use crate::clock::GameClock;
+use crate::menu::Button;
+use crate::navigation::Navigation;
+
+// A dash moves the player this far instantly, then needs the cooldown to recharge
+const DASH_DISTANCE: f32 = 150.0;
+const DASH_COOLDOWN: f32 = 1.5;
+// Gained with every level
+const LEVEL_MAX_HEALTH: f32 = 2.0;
+const LEVEL_MOVEMENT_SPEED: f32 = 5.0;
+pub const DEFAULT_CHARACTER: &str = "Survivor";
 
 pub(crate) struct Player {
     pub position: Vec2,
@@ -12,6 +27,10 @@ pub(crate) struct Player {
     pub sprite: Sprite,
     pub stats: Stats,
     pub weapon: Weapon,
+    pub inventory: Inventory,
+    pub dash_cooldown: f32,
+    pub run_stats: RunStats,
+    pub character: String,
 }
 
 pub struct Stats {
@@ -24,7 +43,10 @@ pub struct Stats {
     pub level: Level,
     pub critical_chance: f32,
     pub critical_damage: f32,
-    pub defense: u32
+    pub defense: u32,
+    pub pickup_radius: f32,
+    pub pickup_speed: f32,
+    pub free_rerolls: u32,
 }
 
 pub struct Weapon {
@@ -69,11 +91,18 @@ pub(crate) struct DamageText {
 
 impl Player {
     pub async fn new() -> Self {
-        let player = Sprite::new(load_texture("assets/player/player_s_1.png").await.unwrap(), Vec2::new(50.0,50.0), 8.0, 8.0, 1.0);
+        let sprite = Sprite::new(load_texture("assets/player/player_s_1.png").await.unwrap(), Vec2::new(50.0,50.0), 8.0, 8.0, 1.0);
+        let mut player = Player::with_sprite(sprite);
+        player.position = utilities::random_position((25.0..screen_width(), 25.0..screen_height()));
+        player
+    }
+
+    // Starting stats around an already loaded sprite, placed at the origin
+    pub fn with_sprite(sprite: Sprite) -> Self {
         Player {
-            position: utilities::random_position((25.0..screen_width(), 25.0..screen_height())),
+            position: Vec2::ZERO,
             size: Vec2::new(50.0,50.0),
-            sprite: player,
+            sprite,
             stats: Stats {
                 base_attack_speed: 1.2,
                 attack_speed_modifier: 1.0,
@@ -84,7 +113,10 @@ impl Player {
                 level: Level::new(0, 0.0, 20.0),
                 critical_chance: 0.01,
                 critical_damage: 0.01,
-                defense: 0
+                defense: 0,
+                pickup_radius: 150.0,
+                pickup_speed: 300.0,
+                free_rerolls: 0,
             },
             weapon: Weapon {
                 count: 3,
@@ -99,6 +131,10 @@ impl Player {
                 circle_radius: 40.0,
                 aoe_count: 3,
             },
+            inventory: Inventory::new(),
+            dash_cooldown: 0.0,
+            run_stats: RunStats::new(),
+            character: DEFAULT_CHARACTER.to_string(),
         }
     }
 
@@ -106,32 +142,20 @@ impl Player {
         self.sprite.draw(self.position);
     }
 
-    pub async fn movement(&mut self) {
+    pub async fn movement(&mut self, dt: f32) {
 
-        let mut direction = Vec2::ZERO;
+        // Keyboard and gamepad combined, never longer than 1 so speed stays consistent
+        let direction = input::movement();
 
-        // Update direction based on input
-        if is_key_down(KeyCode::D) {
-            direction.x += 1.0;
-        }
-        if is_key_down(KeyCode::A) {
-            direction.x -= 1.0;
-        }
-        if is_key_down(KeyCode::W) {
-            direction.y -= 1.0;
-        }
-        if is_key_down(KeyCode::S) {
-            direction.y += 1.0;
-        }
+        // Update player position based on direction and speed
+        self.position += direction * self.stats.movement_speed * dt;
 
-        // Normalize direction to have consistent speed
-        if direction.length() > 0.0 {
-            direction = direction.normalize();
+        self.dash_cooldown = (self.dash_cooldown - dt).max(0.0);
+        if input::action_pressed(Action::Dash) && self.dash_cooldown <= 0.0 && direction.length() > 0.0 {
+            self.position += direction.normalize() * DASH_DISTANCE;
+            self.dash_cooldown = DASH_COOLDOWN;
         }
 
-        // Update player position based on normalized direction and speed
-        self.position += direction * self.stats.movement_speed * get_frame_time();
-
         // Keep the player within the screen bounds
         self.position.x = self.position.x.clamp(0.0, screen_width() - self.size.x);
         self.position.y = self.position.y.clamp(0.0, screen_height() - self.size.y);
@@ -163,6 +187,32 @@ impl Player {
         Player::draw_player_additional(self);
     }
 
+    // Overlay shown after levelling up, returns true once the player continues.
+    // The bonuses shown add up every level gained since the last overlay.
+    pub fn draw_level_up(&self, levels: u32, nav: &mut Navigation) -> bool {
+        draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.6));
+        let title = format!("LEVEL {}", self.stats.level.level);
+        let title_measurements = measure_text(&title, None, 90, 1.0);
+        draw_text(&title, screen_width() / 2.0 - title_measurements.width / 2.0, screen_height() / 2.0 - 100.0, 90.0, GOLD);
+
+        let mut bonuses = Vec::new();
+        if levels > 1 {
+            bonuses.push(format!("{} levels gained", levels));
+        }
+        bonuses.push(format!("+{} Max Health", LEVEL_MAX_HEALTH * levels as f32));
+        bonuses.push(format!("+{} Movement Speed", LEVEL_MOVEMENT_SPEED * levels as f32));
+        bonuses.push("Attack speed, crit chance and crit damage up".to_string());
+        bonuses.push("Health restored".to_string());
+        for (i, bonus) in bonuses.iter().enumerate() {
+            let measurements = measure_text(bonus, None, 30, 1.0);
+            draw_text(bonus, screen_width() / 2.0 - measurements.width / 2.0, screen_height() / 2.0 - 30.0 + i as f32 * 35.0, 30.0, WHITE);
+        }
+
+        let mut continue_btn = Button::new("CONTINUE");
+        continue_btn.set_position(Vec2::new(screen_width() / 2.0 - 100.0, screen_height() / 2.0 + 130.0));
+        continue_btn.draw(nav)
+    }
+
     fn draw_player_bars(percentage: f32, bar_y_offset: f32, bar_width: f32, bar_height: f32, color_1: Color, color_2: Color,color_3: Color, message: &String) {
         draw_rectangle(10.0, bar_y_offset, bar_width, bar_height, color_1);
         draw_rectangle(10.0, bar_y_offset, bar_width * percentage, bar_height, color_2);
@@ -241,29 +291,49 @@ impl Player {
         })
     }
 
-    pub fn collect_coin(&mut self, money: &mut Money, attraction_radius: f32) {
-        let distance = self.position.distance(money.position);
+    // Pulls a pickup towards the player, returns true once it has reached them
+    pub fn attract(&self, position: &mut Vec2, attraction_radius: f32, dt: f32) -> bool {
+        let distance = self.position.distance(*position);
+        if distance < 5.0 {
+            return true;
+        }
         if distance < attraction_radius {
-            let direction = (self.position - money.position).normalize();
-            money.position += direction * (self.stats.movement_speed / 50.0); // Speed of attraction
+            // Never step past the player when frames are long
+            let step = (self.stats.pickup_speed * dt).min(distance);
+            let direction = (self.position - *position).normalize();
+            *position += direction * step;
+        }
+        false
+    }
 
-            // Check if the coin has reached the player
-            if distance < 5.0 {
-                self.stats.money += money.value;
-                money.collected = true;
-            }
+    pub fn collect_coin(&mut self, money: &mut Money, attraction_radius: f32, dt: f32) {
+        if self.attract(&mut money.position, attraction_radius, dt) {
+            self.stats.money += money.value;
+            self.run_stats.record_coin(money.value);
+            money.collected = true;
+        }
+    }
+
+    pub fn collect_experience(&mut self, orb: &mut Experience, attraction_radius: f32, dt: f32) {
+        if self.attract(&mut orb.position, attraction_radius, dt) {
+            self.level_up(orb.value);
+            orb.collected = true;
         }
     }
 
     fn level_up(&mut self, experience: f32) {
-        if self.stats.level.gain_experience(experience) {
-            self.stats.max_health += 2.0;
-            self.stats.health = self.stats.max_health;
+        let levels_gained = self.stats.level.gain_experience(experience);
+        for _ in 0..levels_gained {
+            self.stats.max_health += LEVEL_MAX_HEALTH;
             self.stats.attack_speed_modifier += 0.005;
-            self.stats.movement_speed += 5.0;
+            self.stats.movement_speed += LEVEL_MOVEMENT_SPEED;
             self.stats.critical_chance += 0.005;
             self.stats.critical_damage += 0.005;
         }
+        if levels_gained > 0 {
+            self.stats.health = self.stats.max_health;
+            events::emit(GameEvent::LevelUp { level: self.stats.level.level });
+        }
     }
 
     pub fn attack_interval(&self) -> f32 {
@@ -296,8 +366,8 @@ impl Attack {
         }
     }
 
-    pub fn update(&mut self) {
-        self.position += self.direction * self.speed * get_frame_time();
+    pub fn update(&mut self, dt: f32) {
+        self.position += self.direction * self.speed * dt;
     }
 
     pub fn is_out_of_bounds(&self, map_width: f32, map_height: f32) -> bool {
@@ -308,7 +378,8 @@ impl Attack {
         self.position.distance(enemy.position) < (enemy.size.length() - 30.0)
     }
 
-    pub fn attack(current_time: f64, player: &mut Player, bullets: &mut Vec<Attack>, enemies: &mut Vec<Enemy>, coins: &mut Vec<Money>, damage_text: &mut DamageText) {
+    pub fn attack(clock: &GameClock, player: &mut Player, bullets: &mut Vec<Attack>, enemies: &mut Vec<Enemy>) {
+        let current_time = clock.time();
         draw_circle_lines(player.position.x, player.position.y, player.weapon.circle_radius, 1.0, LIGHTGRAY);
         if current_time - player.weapon.last_attack_time > player.attack_interval() as f64 {
             if let Some(closest_enemy) = player.find_closest_enemy(&enemies) {
@@ -323,7 +394,7 @@ impl Attack {
 
         for bullet in bullets.iter_mut() {
             bullet.draw();
-            bullet.update();
+            bullet.update(clock.delta());
         }
 
         bullets.retain(|bullet| {
@@ -332,14 +403,14 @@ impl Attack {
             }
             for enemy in enemies.iter_mut() {
                 if bullet.check_collision(enemy) {
-                    return Attack::apply_area_of_effect_damage(enemies, bullet.position, coins, player, damage_text);
+                    return Attack::apply_area_of_effect_damage(enemies, bullet.position, player);
                 }
             }
             true
         });
     }
 
-    fn apply_area_of_effect_damage(enemies: &mut Vec<Enemy>, hit_position: Vec2, money: &mut Vec<Money>, player: &mut Player, damage_text: &mut DamageText) -> bool {
+    fn apply_area_of_effect_damage(enemies: &mut Vec<Enemy>, hit_position: Vec2, player: &mut Player) -> bool {
         let mut enemies_damaged: usize = 0;
         for enemy in enemies.iter_mut() {
             let distance = (enemy.position - hit_position).length();
@@ -348,8 +419,8 @@ impl Attack {
                     if enemies_damaged == player.weapon.aoe_count {
                         break;
                     }
-                    Attack::apply_damage(player, enemy, damage_text);
-                    Attack::enemy_died(enemy, player, money);
+                    Attack::apply_damage(player, enemy);
+                    Attack::enemy_died(enemy, player);
                     enemies_damaged += 1;
                 }
             }
@@ -357,41 +428,30 @@ impl Attack {
         false
     }
 
-    fn apply_damage(player: &mut Player, enemy: &mut Enemy, damage_text: &mut DamageText) {
+    fn apply_damage(player: &Player, enemy: &mut Enemy) {
         let mut damage = player.weapon.damage;
-        let mut font_size = 20.0;
-        let mut message = format!("{}", damage);
-        let mut color = WHITE;
-        if utilities::is_critical_hit(player.stats.critical_chance) {
-            font_size = 30.0;
-            color = RED;
+        let critical = utilities::is_critical_hit(player.stats.critical_chance);
+        if critical {
             damage = player.weapon.damage * (100.0 * player.stats.critical_damage);
-            message = format!("{}!", damage as u32);
         }
         enemy.health -= damage;
-
-        damage_text.add_floating_text(
-            font_size,
-            message,
-            enemy.position,
-            color,
-        );
+        events::emit(GameEvent::EnemyDamaged { position: enemy.position, damage, critical });
     }
 
-    fn enemy_died(enemy: &mut Enemy, player: &mut Player, money: &mut Vec<Money>) {
+    // Drops and kill counts are handled by whoever listens for the event
+    fn enemy_died(enemy: &mut Enemy, player: &Player) {
         if enemy.health <= 0.0 {
-            player.level_up(enemy.experience);
-            money.push(Money {
+            events::emit(GameEvent::EnemyKilled {
+                kind: enemy.kind(),
+                elite: enemy.elite,
                 position: enemy.position,
-                size: vec2(20.0, 20.0),
-                value: utilities::random_number(1..=10) as u32,
-                collected: false,
+                experience: enemy.experience,
             });
             enemy.set_enemy_idle(player);
         }
     }
 
-    pub fn weapon_system(player: &mut Player, closest_enemy: &Enemy, bullets: &mut Vec<Attack>, bullet_texture: &mut Texture2D, sound: &Sound) {
+    pub fn weapon_system(player: &mut Player, closest_enemy: &Enemy, bullets: &mut Vec<Attack>, bullet_texture: &mut Texture2D) {
         let direction = (closest_enemy.position - player.position).normalize();
         let rotation_angle = direction.y.atan2(direction.x);
 
@@ -407,14 +467,14 @@ impl Attack {
         draw_circle_lines(player.position.x, player.position.y, player.weapon.circle_radius, 2.0, BLACK);
         if player.position.distance(closest_enemy.position) < player.weapon.range {
             if player.weapon.can_shoot {
-                play_sound(sound, PlaySoundParams { looped: false, volume: 0.001 });
+                events::emit(GameEvent::Shot);
                 let bullet = Attack::new(player.weapon.position, player.weapon.direction, player.weapon.speed, bullet_texture.clone());
                 bullets.push(bullet);
             }
         }
     }
 
-    pub fn draw_weapon_system(enemies: &mut Vec<Enemy>, player: &mut Player, bullets: &mut Vec<Attack>, bullet_texture: &mut Texture2D, shoot_sound: &Sound) {
+    pub fn draw_weapon_system(enemies: &mut Vec<Enemy>, player: &mut Player, bullets: &mut Vec<Attack>, bullet_texture: &mut Texture2D) {
 
         let mut sorted_enemies = enemies;
         sorted_enemies.sort_by(|a, b| {
@@ -425,7 +485,7 @@ impl Attack {
 
         for i in 0..player.weapon.count {
             if i < sorted_enemies.len() {
-                Attack::weapon_system(player, &sorted_enemies[i], bullets, &mut bullet_texture.clone(), &shoot_sound);
+                Attack::weapon_system(player, &sorted_enemies[i], bullets, &mut bullet_texture.clone());
             }
         }
     }
@@ -442,16 +502,17 @@ impl Level {
         }
     }
 
-    pub fn gain_experience(&mut self, experience: f32) -> bool {
+    // Returns how many levels were gained, a single large reward can cross several thresholds
+    pub fn gain_experience(&mut self, experience: f32) -> u32 {
         self.experience += experience;
-        if self.experience >= self.experience_to_next_level {
+        let mut levels_gained = 0;
+        while self.experience >= self.experience_to_next_level {
             self.level += 1;
             self.experience -= self.experience_to_next_level;
             self.experience_to_next_level *= 1.1125;
-            true
-        } else {
-            false
+            levels_gained += 1;
         }
+        levels_gained
     }
 }
 
@@ -500,6 +561,17 @@ impl DamageText {
         self.floating_texts.push(floating_text);
     }
 
+    // Floating damage numbers over enemies that were hit
+    pub fn handle_event(&mut self, event: &GameEvent) {
+        if let GameEvent::EnemyDamaged { position, damage, critical } = event {
+            if *critical {
+                self.add_floating_text(30.0, format!("{}!", *damage as u32), *position, RED);
+            } else {
+                self.add_floating_text(20.0, format!("{}", damage), *position, WHITE);
+            }
+        }
+    }
+
     pub fn update(&mut self, dt: f32) {
         for text in &mut self.floating_texts {
             text.update(dt);
@@ -512,4 +584,45 @@ impl DamageText {
             text.draw();
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Level;
+
+    #[test]
+    fn gain_experience_below_threshold_gains_nothing() {
+        let mut level = Level::new(0, 0.0, 20.0);
+        assert_eq!(level.gain_experience(19.0), 0);
+        assert_eq!(level.level, 0);
+        assert_eq!(level.experience, 19.0);
+        assert_eq!(level.experience_to_next_level, 20.0);
+    }
+
+    #[test]
+    fn gain_experience_single_level_carries_remainder() {
+        let mut level = Level::new(0, 0.0, 20.0);
+        assert_eq!(level.gain_experience(25.0), 1);
+        assert_eq!(level.level, 1);
+        assert!((level.experience - 5.0).abs() < 0.001);
+        assert!((level.experience_to_next_level - 22.25).abs() < 0.001);
+    }
+
+    #[test]
+    fn gain_experience_multiple_levels_follows_growth_curve() {
+        let mut level = Level::new(0, 0.0, 20.0);
+        // 20.0 + 22.25 + 24.753125 = 67.003125 needed for three levels
+        assert_eq!(level.gain_experience(70.0), 3);
+        assert_eq!(level.level, 3);
+        assert!((level.experience - 2.996875).abs() < 0.001);
+        assert!((level.experience_to_next_level - 27.537852).abs() < 0.001);
+    }
+
+    #[test]
+    fn gain_experience_exact_threshold_levels_up() {
+        let mut level = Level::new(4, 0.0, 20.0);
+        assert_eq!(level.gain_experience(20.0), 1);
+        assert_eq!(level.level, 5);
+        assert_eq!(level.experience, 0.0);
+    }
+}
@@ -0,0 +1,249 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use crate::characters::{Character, CHARACTERS};
+use crate::player::{Player, DEFAULT_CHARACTER};
+use crate::shop::MAX_WEAPON_COUNT;
+
+// Meta progression that carries over between runs, plain key=value lines like the settings file
+const PROFILE_FILE: &str = "profile.cfg";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Upgrade {
+    StartingMoney,
+    WeaponCount,
+    ExtraReroll,
+}
+
+// Shop items that only show up once they have been bought with tokens
+pub const ITEM_UNLOCKS: [(&str, u32); 4] = [
+    ("Glorious Ring", 100),
+    ("Multi-Weapon Mastery", 200),
+    ("Triple Weapon Slot", 250),
+    ("Nuclear ammo", 300),
+];
+
+impl Upgrade {
+    pub const ALL: [Upgrade; 3] = [Upgrade::StartingMoney, Upgrade::WeaponCount, Upgrade::ExtraReroll];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Upgrade::StartingMoney => "Starting money",
+            Upgrade::WeaponCount => "Starting weapons",
+            Upgrade::ExtraReroll => "Free rerolls",
+        }
+    }
+
+    fn id(&self) -> &'static str {
+        match self {
+            Upgrade::StartingMoney => "starting_money",
+            Upgrade::WeaponCount => "weapon_count",
+            Upgrade::ExtraReroll => "extra_reroll",
+        }
+    }
+
+    fn from_id(id: &str) -> Option<Upgrade> {
+        Upgrade::ALL.into_iter().find(|upgrade| upgrade.id() == id)
+    }
+
+    pub fn max_level(&self) -> u32 {
+        match self {
+            Upgrade::StartingMoney => 5,
+            Upgrade::WeaponCount => 2,
+            Upgrade::ExtraReroll => 3,
+        }
+    }
+
+    // Price of the next level when the upgrade is at the given one
+    pub fn cost(&self, level: u32) -> u32 {
+        let base = match self {
+            Upgrade::StartingMoney => 50,
+            Upgrade::WeaponCount => 150,
+            Upgrade::ExtraReroll => 40,
+        };
+        base * (level + 1)
+    }
+
+    fn apply(&self, level: u32, player: &mut Player) {
+        match self {
+            Upgrade::StartingMoney => player.stats.money += 100 * level,
+            Upgrade::WeaponCount => player.weapon.count = (player.weapon.count + level as usize).min(MAX_WEAPON_COUNT),
+            Upgrade::ExtraReroll => player.stats.free_rerolls += level,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Profile {
+    pub tokens: u32,
+    upgrades: BTreeMap<Upgrade, u32>,
+    // Names of unlocked characters and items
+    unlocked: BTreeSet<String>,
+    pub character: String,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Profile {
+            tokens: 0,
+            upgrades: BTreeMap::new(),
+            unlocked: BTreeSet::new(),
+            character: DEFAULT_CHARACTER.to_string(),
+        }
+    }
+}
+
+impl Profile {
+    pub fn upgrade_level(&self, upgrade: Upgrade) -> u32 {
+        self.upgrades.get(&upgrade).copied().unwrap_or(0)
+    }
+
+    // Returns false when the upgrade is maxed or can't be afforded
+    pub fn buy_upgrade(&mut self, upgrade: Upgrade) -> bool {
+        let level = self.upgrade_level(upgrade);
+        let cost = upgrade.cost(level);
+        if level >= upgrade.max_level() || self.tokens < cost {
+            return false;
+        }
+        self.tokens -= cost;
+        self.upgrades.insert(upgrade, level + 1);
+        true
+    }
+
+    // Characters and items that cost nothing are always unlocked
+    pub fn is_unlocked(&self, name: &str) -> bool {
+        unlock_cost(name).is_none_or(|cost| cost == 0) || self.unlocked.contains(name)
+    }
+
+    pub fn buy_unlock(&mut self, name: &str) -> bool {
+        let Some(cost) = unlock_cost(name) else {
+            return false;
+        };
+        if self.is_unlocked(name) || self.tokens < cost {
+            return false;
+        }
+        self.tokens -= cost;
+        self.unlocked.insert(name.to_string());
+        true
+    }
+
+    fn to_lines(&self) -> String {
+        let mut contents = format!("tokens={}\ncharacter={}\n", self.tokens, self.character);
+        for (upgrade, level) in self.upgrades.iter() {
+            contents.push_str(&format!("upgrade.{}={}\n", upgrade.id(), level));
+        }
+        for name in self.unlocked.iter() {
+            contents.push_str(&format!("unlock={}\n", name));
+        }
+        contents
+    }
+
+    fn from_lines(contents: &str) -> Self {
+        let mut profile = Profile::default();
+        for line in contents.lines() {
+            let Some((name, value)) = line.split_once('=') else {
+                continue;
+            };
+            let (name, value) = (name.trim(), value.trim());
+            if let Some(id) = name.strip_prefix("upgrade.") {
+                if let (Some(upgrade), Ok(level)) = (Upgrade::from_id(id), value.parse::<u32>()) {
+                    profile.upgrades.insert(upgrade, level.min(upgrade.max_level()));
+                }
+                continue;
+            }
+            match name {
+                "tokens" => profile.tokens = value.parse().unwrap_or(0),
+                "character" if Character::find(value).is_some() => profile.character = value.to_string(),
+                "unlock" if unlock_cost(value).is_some() => {
+                    profile.unlocked.insert(value.to_string());
+                }
+                _ => {}
+            }
+        }
+        profile
+    }
+}
+
+thread_local! {
+    static PROFILE: RefCell<Profile> = RefCell::new(Profile::default());
+}
+
+pub fn current() -> Profile {
+    PROFILE.with(|profile| profile.borrow().clone())
+}
+
+// Changes the profile and writes it to disk, returns whatever the change returned
+pub fn modify<T>(change: impl FnOnce(&mut Profile) -> T) -> T {
+    let result = PROFILE.with(|profile| change(&mut profile.borrow_mut()));
+    save();
+    result
+}
+
+fn unlock_cost(name: &str) -> Option<u32> {
+    CHARACTERS.iter().find(|character| character.name == name).map(|character| character.cost)
+        .or_else(|| ITEM_UNLOCKS.iter().find(|(item, _)| *item == name).map(|(_, cost)| *cost))
+}
+
+pub fn item_available(name: &str) -> bool {
+    PROFILE.with(|profile| profile.borrow().is_unlocked(name))
+}
+
+// The selected character and every bought upgrade, applied to a freshly created player
+pub fn apply_to_player(player: &mut Player) {
+    let profile = current();
+    let character = Character::find(&profile.character)
+        .filter(|character| profile.is_unlocked(character.name))
+        .unwrap_or(&CHARACTERS[0]);
+    character.apply(player);
+    for upgrade in Upgrade::ALL {
+        upgrade.apply(profile.upgrade_level(upgrade), player);
+    }
+}
+
+// Reads the profile file, a missing file starts a fresh profile
+pub fn load() {
+    let profile = fs::read_to_string(PROFILE_FILE)
+        .map(|contents| Profile::from_lines(&contents))
+        .unwrap_or_default();
+    PROFILE.with(|current| *current.borrow_mut() = profile);
+}
+
+pub fn save() {
+    // Failing to write the profile shouldn't stop the game
+    let _ = fs::write(PROFILE_FILE, current().to_lines());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upgrades_cost_tokens_and_stop_at_max_level() {
+        let mut profile = Profile { tokens: 1000, ..Profile::default() };
+        assert!(profile.buy_upgrade(Upgrade::WeaponCount));
+        assert!(profile.buy_upgrade(Upgrade::WeaponCount));
+        assert!(!profile.buy_upgrade(Upgrade::WeaponCount));
+        assert_eq!(profile.tokens, 1000 - 150 - 300);
+        assert_eq!(profile.upgrade_level(Upgrade::WeaponCount), 2);
+    }
+
+    #[test]
+    fn unlocks_need_enough_tokens() {
+        let mut profile = Profile { tokens: 120, ..Profile::default() };
+        assert!(profile.is_unlocked(DEFAULT_CHARACTER));
+        assert!(!profile.is_unlocked("Gunner"));
+        assert!(!profile.buy_unlock("Gunner"));
+        assert!(profile.buy_unlock("Glorious Ring"));
+        assert!(profile.is_unlocked("Glorious Ring"));
+        assert_eq!(profile.tokens, 20);
+    }
+
+    #[test]
+    fn profile_round_trips_through_lines() {
+        let mut profile = Profile { tokens: 1000, ..Profile::default() };
+        profile.buy_upgrade(Upgrade::StartingMoney);
+        profile.buy_unlock("Runner");
+        profile.character = "Runner".to_string();
+        assert_eq!(Profile::from_lines(&profile.to_lines()), profile);
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::BTreeMap;
+use crate::events::GameEvent;
+
+// Score for each wave reached, more than the rest of a run can add up to
+const WAVE_SCORE: u32 = 100_000;
+
+// Numbers collected over a single run for the summary screen
+#[derive(Default)]
+pub(crate) struct RunStats {
+    // Keyed by enemy kind, sorted so the summary always lists kinds in the same order
+    pub kills: BTreeMap<&'static str, u32>,
+    pub damage_dealt: f32,
+    pub hits: u32,
+    pub critical_hits: u32,
+    pub damage_taken: f32,
+    pub money_earned: u32,
+    // Coins picked up since the last wave ended, shown in the income breakdown
+    wave_coins: u32,
+    pub money_spent: u32,
+    pub items_bought: u32,
+}
+
+impl RunStats {
+    pub fn new() -> Self {
+        RunStats::default()
+    }
+
+    pub fn handle_event(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::EnemyDamaged { damage, critical, .. } => self.record_hit(*damage, *critical),
+            GameEvent::EnemyKilled { kind, .. } => self.record_kill(kind),
+            GameEvent::PlayerHit { damage } => self.record_damage_taken(*damage),
+            GameEvent::ItemPurchased { price, .. } => self.record_purchase(*price),
+            _ => {}
+        }
+    }
+
+    pub fn record_hit(&mut self, damage: f32, critical: bool) {
+        self.damage_dealt += damage;
+        self.hits += 1;
+        if critical {
+            self.critical_hits += 1;
+        }
+    }
+
+    pub fn record_kill(&mut self, kind: &'static str) {
+        *self.kills.entry(kind).or_insert(0) += 1;
+    }
+
+    pub fn record_damage_taken(&mut self, damage: f32) {
+        self.damage_taken += damage;
+    }
+
+    pub fn record_earned(&mut self, amount: u32) {
+        self.money_earned += amount;
+    }
+
+    pub fn record_coin(&mut self, amount: u32) {
+        self.wave_coins += amount;
+        self.record_earned(amount);
+    }
+
+    // Coins picked up during the wave that just ended, the count starts over for the next one
+    pub fn take_wave_coins(&mut self) -> u32 {
+        std::mem::take(&mut self.wave_coins)
+    }
+
+    pub fn record_spent(&mut self, amount: u32) {
+        self.money_spent += amount;
+    }
+
+    // Selling an item gives part of its price back, that lowers what was spent rather than counting as earned
+    pub fn record_refund(&mut self, amount: u32) {
+        self.money_spent = self.money_spent.saturating_sub(amount);
+    }
+
+    pub fn record_purchase(&mut self, price: u32) {
+        self.items_bought += 1;
+        self.record_spent(price);
+    }
+
+    pub fn total_kills(&self) -> u32 {
+        self.kills.values().sum()
+    }
+
+    // The wave reached always ranks first. 10 per kill, 1 per 100 damage and 1 per 2 money earned only order runs
+    // that got to the same wave, so together they stay below what one more wave is worth.
+    pub fn score(&self, wave: u32) -> u32 {
+        let performance = (self.total_kills() * 10)
+            .saturating_add((self.damage_dealt / 100.0) as u32)
+            .saturating_add(self.money_earned / 2);
+        wave.saturating_mul(WAVE_SCORE).saturating_add(performance.min(WAVE_SCORE - 1))
+    }
+
+    // Meta currency paid out when the run ends
+    pub fn tokens(&self, wave: u32) -> u32 {
+        wave * 5 + self.total_kills() / 20
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn score_adds_up_the_weighted_parts() {
+        let mut stats = RunStats::new();
+        for _ in 0..50 {
+            stats.record_kill("Normal");
+        }
+        stats.record_hit(1000.0, false);
+        stats.record_earned(500);
+        assert_eq!(stats.score(2), 2 * WAVE_SCORE + 500 + 10 + 250);
+    }
+
+    #[test]
+    fn a_later_wave_scores_higher() {
+        let mut busy = RunStats::new();
+        for _ in 0..20_000 {
+            busy.record_kill("Normal");
+        }
+        busy.record_hit(10_000_000.0, false);
+        busy.record_earned(1_000_000);
+        assert!(RunStats::new().score(4) > busy.score(3));
+    }
+
+    #[test]
+    fn events_update_the_matching_stats() {
+        let mut stats = RunStats::new();
+        stats.handle_event(&GameEvent::EnemyDamaged { position: Default::default(), damage: 25.0, critical: true });
+        stats.handle_event(&GameEvent::EnemyKilled { kind: "Elite", elite: true, position: Default::default(), experience: 5.0 });
+        stats.handle_event(&GameEvent::PlayerHit { damage: 2.0 });
+        stats.handle_event(&GameEvent::WaveStarted { wave: 2 });
+        assert_eq!((stats.hits, stats.critical_hits, stats.damage_dealt), (1, 1, 25.0));
+        assert_eq!(stats.kills.get("Elite"), Some(&1));
+        assert_eq!(stats.damage_taken, 2.0);
+    }
+
+    #[test]
+    fn refunds_lower_spending_and_wave_coins_reset_each_wave() {
+        let mut stats = RunStats::new();
+        stats.record_coin(30);
+        stats.record_earned(100);
+        stats.record_spent(80);
+        stats.record_refund(40);
+        assert_eq!(stats.take_wave_coins(), 30);
+        assert_eq!(stats.take_wave_coins(), 0);
+        assert_eq!((stats.money_earned, stats.money_spent), (130, 40));
+    }
+}
@@ -0,0 +1,161 @@
+use std::cell::RefCell;
+use std::fs;
+use macroquad::prelude::*;
+use crate::input::{self, Action};
+
+// Plain key=value lines next to the executable's working directory
+const SETTINGS_FILE: &str = "settings.cfg";
+
+pub const RESOLUTIONS: [(u32, u32); 4] = [(1280, 720), (1600, 900), (1920, 1080), (2560, 1440)];
+// 0 leaves the frame rate to vsync
+pub const FRAME_CAPS: [u32; 5] = [0, 30, 60, 120, 144];
+// Only the in-run HUD is scaled, menus and overlays keep their size
+pub const UI_SCALES: [f32; 4] = [0.75, 1.0, 1.25, 1.5];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Settings {
+    pub fullscreen: bool,
+    pub resolution: usize,
+    pub vsync: bool,
+    pub frame_cap: usize,
+    pub ui_scale: usize,
+    pub master_volume: f32,
+    pub sfx_volume: f32,
+    pub music_volume: f32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            fullscreen: true,
+            resolution: 0,
+            vsync: true,
+            frame_cap: 0,
+            ui_scale: 1,
+            master_volume: 1.0,
+            sfx_volume: 1.0,
+            music_volume: 1.0,
+        }
+    }
+}
+
+thread_local! {
+    static SETTINGS: RefCell<Settings> = RefCell::new(Settings::default());
+    // Time the last frame finished, used by the frame cap
+    static LAST_FRAME: RefCell<f64> = const { RefCell::new(0.0) };
+}
+
+pub fn current() -> Settings {
+    SETTINGS.with(|settings| *settings.borrow())
+}
+
+// Replaces the settings, applies them to the window and writes them to disk
+pub fn update(settings: Settings) {
+    let previous = current();
+    SETTINGS.with(|current| *current.borrow_mut() = settings);
+    if previous.fullscreen != settings.fullscreen || previous.resolution != settings.resolution {
+        apply_window();
+    }
+    save();
+}
+
+pub fn apply_window() {
+    let settings = current();
+    set_fullscreen(settings.fullscreen);
+    if !settings.fullscreen {
+        let (width, height) = RESOLUTIONS[settings.resolution];
+        request_new_screen_size(width as f32, height as f32);
+    }
+}
+
+pub fn ui_scale() -> f32 {
+    UI_SCALES[current().ui_scale]
+}
+
+// Volume for sound effects after the master volume is applied
+pub fn sfx_volume() -> f32 {
+    let settings = current();
+    settings.master_volume * settings.sfx_volume
+}
+
+// Sleeps out the rest of the frame when a frame cap is set, call right before next_frame
+pub fn limit_frame_rate() {
+    let cap = FRAME_CAPS[current().frame_cap];
+    LAST_FRAME.with(|last_frame| {
+        let mut last_frame = last_frame.borrow_mut();
+        if cap > 0 {
+            let remaining = 1.0 / cap as f64 - (get_time() - *last_frame);
+            if remaining > 0.0 {
+                std::thread::sleep(std::time::Duration::from_secs_f64(remaining));
+            }
+        }
+        *last_frame = get_time();
+    });
+}
+
+// Reads the settings file and applies it, a missing or broken file just leaves the defaults
+pub fn load() {
+    let Ok(contents) = fs::read_to_string(SETTINGS_FILE) else {
+        return;
+    };
+    let mut settings = Settings::default();
+    for line in contents.lines() {
+        let Some((name, value)) = line.split_once('=') else {
+            continue;
+        };
+        let (name, value) = (name.trim(), value.trim());
+        if let Some(id) = name.strip_prefix("bind.") {
+            if let (Some(action), Some(key)) = (Action::from_id(id), input::key_from_name(value)) {
+                input::set_binding(action, key);
+            }
+            continue;
+        }
+        match name {
+            "fullscreen" => settings.fullscreen = value.parse().unwrap_or(settings.fullscreen),
+            "resolution" => {
+                settings.resolution = RESOLUTIONS.iter()
+                    .position(|(width, height)| format!("{}x{}", width, height) == value)
+                    .unwrap_or(settings.resolution);
+            }
+            "vsync" => settings.vsync = value.parse().unwrap_or(settings.vsync),
+            "frame_cap" => {
+                settings.frame_cap = value.parse::<u32>().ok()
+                    .and_then(|cap| FRAME_CAPS.iter().position(|option| *option == cap))
+                    .unwrap_or(settings.frame_cap);
+            }
+            "ui_scale" => {
+                settings.ui_scale = value.parse::<f32>().ok()
+                    .and_then(|scale| UI_SCALES.iter().position(|option| *option == scale))
+                    .unwrap_or(settings.ui_scale);
+            }
+            "master_volume" => settings.master_volume = parse_volume(value, settings.master_volume),
+            "sfx_volume" => settings.sfx_volume = parse_volume(value, settings.sfx_volume),
+            "music_volume" => settings.music_volume = parse_volume(value, settings.music_volume),
+            _ => {}
+        }
+    }
+    SETTINGS.with(|current| *current.borrow_mut() = settings);
+}
+
+fn parse_volume(value: &str, fallback: f32) -> f32 {
+    value.parse::<f32>().map(|volume| volume.clamp(0.0, 1.0)).unwrap_or(fallback)
+}
+
+pub fn save() {
+    let settings = current();
+    let (width, height) = RESOLUTIONS[settings.resolution];
+    let mut contents = String::new();
+    contents.push_str(&format!("fullscreen={}\n", settings.fullscreen));
+    contents.push_str(&format!("resolution={}x{}\n", width, height));
+    contents.push_str(&format!("vsync={}\n", settings.vsync));
+    contents.push_str(&format!("frame_cap={}\n", FRAME_CAPS[settings.frame_cap]));
+    contents.push_str(&format!("ui_scale={}\n", UI_SCALES[settings.ui_scale]));
+    contents.push_str(&format!("master_volume={:.1}\n", settings.master_volume));
+    contents.push_str(&format!("sfx_volume={:.1}\n", settings.sfx_volume));
+    contents.push_str(&format!("music_volume={:.1}\n", settings.music_volume));
+    for action in input::REBINDABLE {
+        contents.push_str(&format!("bind.{}={}\n", action.id(), input::key_name(action.key())));
+    }
+    // Failing to write settings shouldn't stop the game
+    let _ = fs::write(SETTINGS_FILE, contents);
+}
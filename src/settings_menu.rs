@@ -0,0 +1,91 @@
+use macroquad::prelude::*;
+use crate::background;
+use crate::menu::Button;
+use crate::navigation::Navigation;
+use crate::settings::{self, FRAME_CAPS, RESOLUTIONS, UI_SCALES};
+
+const ROW_HEIGHT: f32 = 60.0;
+const VOLUME_STEP: f32 = 0.1;
+
+// Draws one frame of the settings screen, returns true when the player goes back.
+// Every change is applied immediately and written to the settings file.
+pub(crate) fn settings_screen(background_texture: &Texture2D, nav: &mut Navigation) -> bool {
+    background::draw(background_texture);
+    draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.7));
+
+    let mut settings = settings::current();
+    let top = screen_height() / 2.0 - 4.5 * ROW_HEIGHT;
+    let label_x = screen_width() / 2.0 - 300.0;
+    let control_x = screen_width() / 2.0 + 20.0;
+    draw_text("SETTINGS", screen_width() / 2.0 - 90.0, top - 40.0, 50.0, WHITE);
+
+    let display = if settings.fullscreen { "FULLSCREEN" } else { "WINDOWED" };
+    if option_row("Display", display, Vec2::new(label_x, top), control_x, nav) {
+        settings.fullscreen = !settings.fullscreen;
+    }
+
+    let (width, height) = RESOLUTIONS[settings.resolution];
+    if option_row("Window size", &format!("{}x{}", width, height), Vec2::new(label_x, top + ROW_HEIGHT), control_x, nav) {
+        settings.resolution = (settings.resolution + 1) % RESOLUTIONS.len();
+    }
+
+    let vsync = if settings.vsync { "ON" } else { "OFF" };
+    if option_row("VSync (restart)", vsync, Vec2::new(label_x, top + ROW_HEIGHT * 2.0), control_x, nav) {
+        settings.vsync = !settings.vsync;
+    }
+
+    let frame_cap = match FRAME_CAPS[settings.frame_cap] {
+        0 => "UNLIMITED".to_string(),
+        cap => format!("{} FPS", cap),
+    };
+    if option_row("Frame cap", &frame_cap, Vec2::new(label_x, top + ROW_HEIGHT * 3.0), control_x, nav) {
+        settings.frame_cap = (settings.frame_cap + 1) % FRAME_CAPS.len();
+    }
+
+    let ui_scale = format!("{:.0}%", UI_SCALES[settings.ui_scale] * 100.0);
+    if option_row("HUD scale", &ui_scale, Vec2::new(label_x, top + ROW_HEIGHT * 4.0), control_x, nav) {
+        settings.ui_scale = (settings.ui_scale + 1) % UI_SCALES.len();
+    }
+
+    volume_row("Master volume", &mut settings.master_volume, Vec2::new(label_x, top + ROW_HEIGHT * 5.0), control_x, nav);
+    volume_row("Effects volume", &mut settings.sfx_volume, Vec2::new(label_x, top + ROW_HEIGHT * 6.0), control_x, nav);
+    volume_row("Music volume", &mut settings.music_volume, Vec2::new(label_x, top + ROW_HEIGHT * 7.0), control_x, nav);
+
+    if settings != settings::current() {
+        settings::update(settings);
+    }
+
+    let mut back_btn = Button::new("BACK");
+    back_btn.set_position(Vec2::new(screen_width() / 2.0 - 100.0, top + ROW_HEIGHT * 8.0 + 20.0));
+    back_btn.draw(nav)
+}
+
+// A label with a button showing the current value, returns true when the value should advance
+fn option_row(label: &str, value: &str, label_position: Vec2, control_x: f32, nav: &mut Navigation) -> bool {
+    draw_text(label, label_position.x, label_position.y + 35.0, 30.0, WHITE);
+    let mut button = Button::new(value);
+    button.set_position(Vec2::new(control_x, label_position.y));
+    button.draw(nav)
+}
+
+fn volume_row(label: &str, volume: &mut f32, label_position: Vec2, control_x: f32, nav: &mut Navigation) {
+    draw_text(label, label_position.x, label_position.y + 35.0, 30.0, WHITE);
+
+    let mut down_btn = Button::new("-");
+    down_btn.set_size(Vec2::new(50.0, 50.0));
+    down_btn.set_position(Vec2::new(control_x, label_position.y));
+    if down_btn.draw(nav) {
+        *volume = (*volume - VOLUME_STEP).max(0.0);
+    }
+
+    let bar_position = Vec2::new(control_x + 60.0, label_position.y + 15.0);
+    draw_rectangle(bar_position.x, bar_position.y, 180.0, 20.0, GRAY);
+    draw_rectangle(bar_position.x, bar_position.y, 180.0 * *volume, 20.0, GREEN);
+
+    let mut up_btn = Button::new("+");
+    up_btn.set_size(Vec2::new(50.0, 50.0));
+    up_btn.set_position(Vec2::new(control_x + 250.0, label_position.y));
+    if up_btn.draw(nav) {
+        *volume = (*volume + VOLUME_STEP).min(1.0);
+    }
+}
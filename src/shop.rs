@@ -5,22 +5,57 @@ mod items;
 mod menu;
 pub(crate) mod textures;
 mod logic;
+mod synergy;
+
+pub(crate) use logic::MAX_WEAPON_COUNT;
 
 use crate::player::Player;
+use crate::navigation::Navigation;
+
+// Reroll cost starts from this every wave and grows by the per-wave amount
+const REROLL_BASE_COST: u32 = 2;
+const REROLL_COST_PER_WAVE: u32 = 2;
 
 pub(crate) struct Shop {
     pub reroll_count: u32,
     pub reroll_cost: u32,
+    pub income: Income,
+}
+
+// Where the money earned during the last wave came from, shown on the shop screen
+#[derive(Default)]
+pub struct Income {
+    pub collected: u32,
+    pub harvested: u32,
+    pub wave_bonus: u32,
+    pub interest: u32,
+}
+
+impl Income {
+    pub fn total(&self) -> u32 {
+        self.collected + self.harvested + self.wave_bonus + self.interest
+    }
 }
 
 impl Shop {
     pub fn new() -> Self {
         Shop {
-            reroll_cost: 2, // Starting value
+            reroll_cost: Shop::base_reroll_cost(1),
             reroll_count: 0,
+            income: Income::default(),
         }
     }
 
+    pub fn base_reroll_cost(wave: u32) -> u32 {
+        REROLL_BASE_COST + wave.saturating_sub(1) * REROLL_COST_PER_WAVE
+    }
+
+    // Rerolls get expensive within a shop visit but start over every wave
+    pub fn reset_reroll_cost(&mut self, wave: u32) {
+        self.reroll_count = 0;
+        self.reroll_cost = Shop::base_reroll_cost(wave);
+    }
+
     pub fn increment_reroll_cost(&mut self) -> u32 {
         self.reroll_count += 1;
 
@@ -34,13 +69,60 @@ impl Shop {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum Rarity {
+    Common,
+    Uncommon,
+    Rare,
+    Epic,
+    Legendary,
+}
+
+impl Rarity {
+    pub const ALL: [Rarity; 5] = [Rarity::Common, Rarity::Uncommon, Rarity::Rare, Rarity::Epic, Rarity::Legendary];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rarity::Common => "Common",
+            Rarity::Uncommon => "Uncommon",
+            Rarity::Rare => "Rare",
+            Rarity::Epic => "Epic",
+            Rarity::Legendary => "Legendary",
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            Rarity::Common => BLACK,
+            Rarity::Uncommon => DARKGREEN,
+            Rarity::Rare => BLUE,
+            Rarity::Epic => PURPLE,
+            Rarity::Legendary => ORANGE,
+        }
+    }
+
+    // Roll weights for every rarity, rarer tiers become more likely as the run goes on
+    pub fn weights(wave: u32, level: u32) -> [f32; 5] {
+        let base = [60.0, 25.0, 10.0, 4.0, 1.0];
+        let luck = 1.0 + wave as f32 * 0.15 + level as f32 * 0.05;
+        let mut weights = [0.0; 5];
+        for (i, weight) in base.iter().enumerate() {
+            weights[i] = weight * luck.powi(i as i32);
+        }
+        weights
+    }
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct Item {
     pub texture_name: String,
     pub name: String,
     pub price: u32,
+    pub rarity: Rarity,
     pub description: String,
     pub level_requirement: u32,
+    // Owning several items with the same tag unlocks a synergy bonus
+    pub tags: Vec<String>,
     pub attributes: Attributes
 }
 
@@ -51,17 +133,30 @@ pub struct Attributes {
     pub(crate) duration: u32,
 }
 
+#[derive(Clone)]
 pub struct ShopTextures {
     textures: HashMap<String, Texture2D>,
 }
 
 impl Item {
-    pub fn shop(level: u32) -> Vec<Item> {
-        items::get_four_items_from_list(level)
+    // Consumables are used up on purchase and never end up in the inventory
+    pub fn is_consumable(&self) -> bool {
+        matches!(self.attributes.attribute_type.as_str(), "player_health" | "free_reroll")
+    }
+
+    pub fn shop(level: u32, wave: u32) -> Vec<Item> {
+        items::get_four_items_from_list(level, wave)
+    }
+
+    // Fills every unlocked slot with a new item at the start of a shop visit
+    pub fn restock(items: &mut Vec<Item>, locked_slots: &mut Vec<bool>, level: u32, wave: u32) {
+        locked_slots.resize(items.len(), false);
+        let rolled = items::get_four_items_from_list(level, wave);
+        items::reroll_unlocked(items, locked_slots, rolled);
     }
 
-    pub async fn shop_menu(items: &mut Vec<Item>, player: &mut Player, shop_textures: &ShopTextures, shop: &mut Shop) {
-        menu::draw_shop(items, player, shop_textures, shop).await;
+    pub fn shop_menu(items: &mut Vec<Item>, locked_slots: &mut Vec<bool>, player: &mut Player, shop_textures: &ShopTextures, shop: &mut Shop, wave: u32, nav: &mut Navigation) -> bool {
+        menu::draw_shop(items, locked_slots, player, shop_textures, shop, wave, nav)
     }
 }
 
@@ -82,4 +177,38 @@ impl ShopTextures {
     pub fn get_texture(&self, name: &str) -> Option<&Texture2D> {
         self.textures.get(name)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reroll_cost_starts_over_each_wave_and_grows_with_it() {
+        let mut shop = Shop::new();
+        let first_wave = shop.reroll_cost;
+        shop.increment_reroll_cost();
+        shop.increment_reroll_cost();
+        shop.reset_reroll_cost(1);
+        assert_eq!((shop.reroll_cost, shop.reroll_count), (first_wave, 0));
+
+        shop.increment_reroll_cost();
+        shop.reset_reroll_cost(5);
+        assert_eq!(shop.reroll_cost, Shop::base_reroll_cost(5));
+        assert!(Shop::base_reroll_cost(5) > first_wave);
+        assert!(Shop::base_reroll_cost(6) > Shop::base_reroll_cost(5));
+    }
+
+    #[test]
+    fn rarer_weights_grow_with_wave_and_level() {
+        let start = Rarity::weights(1, 1);
+        let later_wave = Rarity::weights(10, 1);
+        let higher_level = Rarity::weights(1, 20);
+        for i in 1..Rarity::ALL.len() {
+            assert!(later_wave[i] > start[i]);
+            assert!(higher_level[i] > start[i]);
+        }
+        // Common items keep their weight, so their share of the roll shrinks
+        assert_eq!(later_wave[0], start[0]);
+        assert!(later_wave[4] / later_wave[0] > start[4] / start[0]);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::shop::*;
 use crate::utilities;
+use crate::profile;
 
 pub fn shop_items() -> Vec<Item> {
     let items = vec![
@@ -7,8 +8,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Speed Boots".to_string(),
             price: 50,
+            rarity: Rarity::Common,
             description: "Increases movement speed by 20%".to_string(),
             level_requirement: 5,
+            tags: vec!["speed".to_string()],
             attributes: Attributes {
                 attribute_type: "movement_speed".to_string(),
                 quantity: 20,
@@ -19,8 +22,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Ring of Criticals".to_string(),
             price: 100,
+            rarity: Rarity::Uncommon,
             description: "Increases critical chance by 5%".to_string(),
             level_requirement: 10,
+            tags: vec!["crit".to_string()],
             attributes: Attributes {
                 attribute_type: "critical_chance".to_string(),
                 quantity: 5,
@@ -31,8 +36,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Gloves of Swiftness".to_string(),
             price: 75,
+            rarity: Rarity::Common,
             description: "Increases attack speed by 15%".to_string(),
             level_requirement: 8,
+            tags: vec!["speed".to_string()],
             attributes: Attributes {
                 attribute_type: "attack_speed".to_string(),
                 quantity: 15,
@@ -43,8 +50,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Iron Shield".to_string(),
             price: 60,
+            rarity: Rarity::Common,
             description: "Increases defense by 30".to_string(),
             level_requirement: 3,
+            tags: vec!["defense".to_string()],
             attributes: Attributes {
                 attribute_type: "defense".to_string(),
                 quantity: 30,
@@ -55,8 +64,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Range Amplifier".to_string(),
             price: 120,
+            rarity: Rarity::Uncommon,
             description: "Increases damage range by 20".to_string(),
             level_requirement: 15,
+            tags: vec!["precision".to_string()],
             attributes: Attributes {
                 attribute_type: "damage_range".to_string(),
                 quantity: 200,
@@ -67,8 +78,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Power Potion".to_string(),
             price: 90,
+            rarity: Rarity::Common,
             description: "Increases weapon damage by 2".to_string(),
             level_requirement: 1,
+            tags: vec!["arsenal".to_string()],
             attributes: Attributes {
                 attribute_type: "weapon_damage".to_string(),
                 quantity: 25,
@@ -79,8 +92,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Bullet of Swiftness".to_string(),
             price: 110,
+            rarity: Rarity::Uncommon,
             description: "Increases bullet speed by 10".to_string(),
             level_requirement: 1,
+            tags: vec!["speed".to_string()],
             attributes: Attributes {
                 attribute_type: "bullet_speed".to_string(),
                 quantity: 100,
@@ -91,8 +106,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Extra Weapon Slot".to_string(),
             price: 1000,
+            rarity: Rarity::Epic,
             description: "Adds an additional weapon".to_string(),
             level_requirement: 20,
+            tags: vec!["arsenal".to_string()],
             attributes: Attributes {
                 attribute_type: "weapon_count".to_string(),
                 quantity: 1,
@@ -103,8 +120,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Ring of Fire".to_string(),
             price: 130,
+            rarity: Rarity::Uncommon,
             description: "Increases damage radius by 20".to_string(),
             level_requirement: 18,
+            tags: vec!["explosive".to_string()],
             attributes: Attributes {
                 attribute_type: "damage_radius".to_string(),
                 quantity: 20,
@@ -115,8 +134,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Large Health Potion".to_string(),
             price: 30,
+            rarity: Rarity::Common,
             description: "Restores 100 health".to_string(),
             level_requirement: 2,
+            tags: Vec::new(),
             attributes: Attributes {
                 attribute_type: "player_health".to_string(),
                 quantity: 100,
@@ -127,8 +148,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Mystic Ring".to_string(),
             price: 500,
+            rarity: Rarity::Epic,
             description: "Increases critical damage by 10%".to_string(),
             level_requirement: 25,
+            tags: vec!["crit".to_string()],
             attributes: Attributes {
                 attribute_type: "critical_damage".to_string(),
                 quantity: 10,
@@ -139,8 +162,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Glorious Ring".to_string(),
             price: 500,
+            rarity: Rarity::Epic,
             description: "Increases critical damage by 8%".to_string(),
             level_requirement: 25,
+            tags: vec!["crit".to_string()],
             attributes: Attributes {
                 attribute_type: "critical_damage".to_string(),
                 quantity: 8,
@@ -151,8 +176,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Warrior's Helm".to_string(),
             price: 80,
+            rarity: Rarity::Common,
             description: "Increases defense by 10".to_string(),
             level_requirement: 1,
+            tags: vec!["defense".to_string()],
             attributes: Attributes {
                 attribute_type: "defense".to_string(),
                 quantity: 10,
@@ -163,8 +190,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Potion of Swiftness".to_string(),
             price: 200,
+            rarity: Rarity::Rare,
             description: "Increases movement speed by 15% for 60 seconds".to_string(),
             level_requirement: 1,
+            tags: vec!["speed".to_string()],
             attributes: Attributes {
                 attribute_type: "movement_speed".to_string(),
                 quantity: 15,
@@ -175,8 +204,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Gloves of Quickness".to_string(),
             price: 5000,
+            rarity: Rarity::Legendary,
             description: "Increases attack speed by 20%".to_string(),
             level_requirement: 50,
+            tags: vec!["speed".to_string()],
             attributes: Attributes {
                 attribute_type: "attack_speed".to_string(),
                 quantity: 20,
@@ -187,8 +218,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Sniper Scope".to_string(),
             price: 150,
+            rarity: Rarity::Uncommon,
             description: "Increases damage range by 50".to_string(),
             level_requirement: 20,
+            tags: vec!["precision".to_string()],
             attributes: Attributes {
                 attribute_type: "damage_range".to_string(),
                 quantity: 50,
@@ -199,8 +232,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Damage Booster".to_string(),
             price: 120,
+            rarity: Rarity::Uncommon,
             description: "Increases weapon damage by 10".to_string(),
             level_requirement: 15,
+            tags: vec!["arsenal".to_string()],
             attributes: Attributes {
                 attribute_type: "weapon_damage".to_string(),
                 quantity: 10,
@@ -211,8 +246,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Bullet Surge".to_string(),
             price: 135,
+            rarity: Rarity::Uncommon,
             description: "Increases bullet speed by 150".to_string(),
             level_requirement: 14,
+            tags: vec!["speed".to_string()],
             attributes: Attributes {
                 attribute_type: "bullet_speed".to_string(),
                 quantity: 150,
@@ -223,8 +260,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Multi-Weapon Mastery".to_string(),
             price: 7500,
+            rarity: Rarity::Legendary,
             description: "Adds 2 additional weapons".to_string(),
             level_requirement: 15,
+            tags: vec!["arsenal".to_string()],
             attributes: Attributes {
                 attribute_type: "weapon_count".to_string(),
                 quantity: 2,
@@ -235,8 +274,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Blast Radius Enhancer".to_string(),
             price: 10000,
+            rarity: Rarity::Legendary,
             description: "Increases damage radius by 100".to_string(),
             level_requirement: 60,
+            tags: vec!["explosive".to_string()],
             attributes: Attributes {
                 attribute_type: "damage_radius".to_string(),
                 quantity: 100,
@@ -247,8 +288,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Giant Health Potion".to_string(),
             price: 45,
+            rarity: Rarity::Common,
             description: "Restores 200 health".to_string(),
             level_requirement: 5,
+            tags: Vec::new(),
             attributes: Attributes {
                 attribute_type: "player_health".to_string(),
                 quantity: 200,
@@ -259,8 +302,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Shadow Boots".to_string(),
             price: 90,
+            rarity: Rarity::Common,
             description: "Increases movement speed by 25%".to_string(),
             level_requirement: 10,
+            tags: vec!["speed".to_string()],
             attributes: Attributes {
                 attribute_type: "movement_speed".to_string(),
                 quantity: 25,
@@ -271,8 +316,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Ring of Power".to_string(),
             price: 140,
+            rarity: Rarity::Uncommon,
             description: "Increases critical chance by 10%".to_string(),
             level_requirement: 15,
+            tags: vec!["crit".to_string()],
             attributes: Attributes {
                 attribute_type: "critical_chance".to_string(),
                 quantity: 10,
@@ -283,8 +330,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Quick Blades".to_string(),
             price: 85,
+            rarity: Rarity::Common,
             description: "Increases attack speed by 18%".to_string(),
             level_requirement: 10,
+            tags: vec!["speed".to_string()],
             attributes: Attributes {
                 attribute_type: "attack_speed".to_string(),
                 quantity: 18,
@@ -295,8 +344,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Guardian Shield".to_string(),
             price: 70,
+            rarity: Rarity::Common,
             description: "Increases defense by 40".to_string(),
             level_requirement: 8,
+            tags: vec!["defense".to_string()],
             attributes: Attributes {
                 attribute_type: "defense".to_string(),
                 quantity: 40,
@@ -307,8 +358,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Sniper Amplifier".to_string(),
             price: 160,
+            rarity: Rarity::Uncommon,
             description: "Increases damage range by 350".to_string(),
             level_requirement: 18,
+            tags: vec!["precision".to_string()],
             attributes: Attributes {
                 attribute_type: "damage_range".to_string(),
                 quantity: 350,
@@ -319,8 +372,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Potion of Power".to_string(),
             price: 110,
+            rarity: Rarity::Uncommon,
             description: "Increases weapon damage by 30".to_string(),
             level_requirement: 13,
+            tags: vec!["arsenal".to_string()],
             attributes: Attributes {
                 attribute_type: "weapon_damage".to_string(),
                 quantity: 30,
@@ -331,8 +386,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Bullet Quickener".to_string(),
             price: 125,
+            rarity: Rarity::Uncommon,
             description: "Increases bullet speed by 120".to_string(),
             level_requirement: 12,
+            tags: vec!["speed".to_string()],
             attributes: Attributes {
                 attribute_type: "bullet_speed".to_string(),
                 quantity: 120,
@@ -343,8 +400,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Double Weapon Slot".to_string(),
             price: 180,
+            rarity: Rarity::Uncommon,
             description: "Adds an additional weapon".to_string(),
             level_requirement: 25,
+            tags: vec!["arsenal".to_string()],
             attributes: Attributes {
                 attribute_type: "weapon_count".to_string(),
                 quantity: 1,
@@ -355,8 +414,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Radius Enhancer".to_string(),
             price: 170,
+            rarity: Rarity::Uncommon,
             description: "Increases damage radius by 70".to_string(),
             level_requirement: 20,
+            tags: vec!["explosive".to_string()],
             attributes: Attributes {
                 attribute_type: "damage_radius".to_string(),
                 quantity: 70,
@@ -367,8 +428,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Super Health Potion".to_string(),
             price: 55,
+            rarity: Rarity::Common,
             description: "Restores 250 health".to_string(),
             level_requirement: 7,
+            tags: Vec::new(),
             attributes: Attributes {
                 attribute_type: "player_health".to_string(),
                 quantity: 250,
@@ -379,8 +442,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Boots of Swiftness".to_string(),
             price: 65,
+            rarity: Rarity::Common,
             description: "Increases movement speed by 12%".to_string(),
             level_requirement: 5,
+            tags: vec!["speed".to_string()],
             attributes: Attributes {
                 attribute_type: "movement_speed".to_string(),
                 quantity: 12,
@@ -391,8 +456,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Necklace of Criticals".to_string(),
             price: 135,
+            rarity: Rarity::Uncommon,
             description: "Increases critical chance by 8%".to_string(),
             level_requirement: 12,
+            tags: vec!["crit".to_string()],
             attributes: Attributes {
                 attribute_type: "critical_chance".to_string(),
                 quantity: 8,
@@ -403,8 +470,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Blades of Speed".to_string(),
             price: 95,
+            rarity: Rarity::Common,
             description: "Increases attack speed by 22%".to_string(),
             level_requirement: 14,
+            tags: vec!["speed".to_string()],
             attributes: Attributes {
                 attribute_type: "attack_speed".to_string(),
                 quantity: 22,
@@ -415,8 +484,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Heavy Shield".to_string(),
             price: 75,
+            rarity: Rarity::Common,
             description: "Increases defense by 45".to_string(),
             level_requirement: 6,
+            tags: vec!["defense".to_string()],
             attributes: Attributes {
                 attribute_type: "defense".to_string(),
                 quantity: 45,
@@ -427,8 +498,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Range Extender".to_string(),
             price: 145,
+            rarity: Rarity::Uncommon,
             description: "Increases damage range by 250".to_string(),
             level_requirement: 17,
+            tags: vec!["precision".to_string()],
             attributes: Attributes {
                 attribute_type: "damage_range".to_string(),
                 quantity: 250,
@@ -439,8 +512,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Weapon Enhancer".to_string(),
             price: 130,
+            rarity: Rarity::Uncommon,
             description: "Increases weapon damage by 40".to_string(),
             level_requirement: 16,
+            tags: vec!["arsenal".to_string()],
             attributes: Attributes {
                 attribute_type: "weapon_damage".to_string(),
                 quantity: 40,
@@ -451,8 +526,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Bullet Speed-Up".to_string(),
             price: 115,
+            rarity: Rarity::Uncommon,
             description: "Increases bullet speed by 90".to_string(),
             level_requirement: 11,
+            tags: vec!["speed".to_string()],
             attributes: Attributes {
                 attribute_type: "bullet_speed".to_string(),
                 quantity: 90,
@@ -463,8 +540,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Triple Weapon Slot".to_string(),
             price: 10000,
+            rarity: Rarity::Legendary,
             description: "Adds 3 additional weapons".to_string(),
             level_requirement: 10,
+            tags: vec!["arsenal".to_string()],
             attributes: Attributes {
                 attribute_type: "weapon_count".to_string(),
                 quantity: 3,
@@ -475,8 +554,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Radius Booster".to_string(),
             price: 160,
+            rarity: Rarity::Uncommon,
             description: "Increases damage radius by 90".to_string(),
             level_requirement: 23,
+            tags: vec!["explosive".to_string()],
             attributes: Attributes {
                 attribute_type: "damage_radius".to_string(),
                 quantity: 90,
@@ -487,8 +568,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Mega Health Potion".to_string(),
             price: 65,
+            rarity: Rarity::Common,
             description: "Restores 300 health".to_string(),
             level_requirement: 8,
+            tags: Vec::new(),
             attributes: Attributes {
                 attribute_type: "player_health".to_string(),
                 quantity: 300,
@@ -499,8 +582,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Winged Boots".to_string(),
             price: 85,
+            rarity: Rarity::Common,
             description: "Increases movement speed by 18%".to_string(),
             level_requirement: 9,
+            tags: vec!["speed".to_string()],
             attributes: Attributes {
                 attribute_type: "movement_speed".to_string(),
                 quantity: 18,
@@ -511,8 +596,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Crown of Criticals".to_string(),
             price: 160,
+            rarity: Rarity::Uncommon,
             description: "Increases critical chance by 12%".to_string(),
             level_requirement: 20,
+            tags: vec!["crit".to_string()],
             attributes: Attributes {
                 attribute_type: "critical_chance".to_string(),
                 quantity: 12,
@@ -523,8 +610,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Quick Sword".to_string(),
             price: 95,
+            rarity: Rarity::Common,
             description: "Increases attack speed by 20%".to_string(),
             level_requirement: 12,
+            tags: vec!["speed".to_string()],
             attributes: Attributes {
                 attribute_type: "attack_speed".to_string(),
                 quantity: 20,
@@ -535,8 +624,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Iron Defender".to_string(),
             price: 85,
+            rarity: Rarity::Common,
             description: "Increases defense by 35".to_string(),
             level_requirement: 9,
+            tags: vec!["defense".to_string()],
             attributes: Attributes {
                 attribute_type: "defense".to_string(),
                 quantity: 35,
@@ -547,8 +638,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Long Range Scope".to_string(),
             price: 160,
+            rarity: Rarity::Uncommon,
             description: "Increases damage range by 300".to_string(),
             level_requirement: 22,
+            tags: vec!["precision".to_string()],
             attributes: Attributes {
                 attribute_type: "damage_range".to_string(),
                 quantity: 300,
@@ -559,8 +652,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Strength Potion".to_string(),
             price: 100,
+            rarity: Rarity::Uncommon,
             description: "Increases weapon damage by 35".to_string(),
             level_requirement: 14,
+            tags: vec!["arsenal".to_string()],
             attributes: Attributes {
                 attribute_type: "weapon_damage".to_string(),
                 quantity: 35,
@@ -571,8 +666,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Accelerated Bullets".to_string(),
             price: 125,
+            rarity: Rarity::Uncommon,
             description: "Increases bullet speed by 130".to_string(),
             level_requirement: 13,
+            tags: vec!["speed".to_string()],
             attributes: Attributes {
                 attribute_type: "bullet_speed".to_string(),
                 quantity: 130,
@@ -583,8 +680,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Extra Weapon Slot".to_string(),
             price: 190,
+            rarity: Rarity::Uncommon,
             description: "Adds an additional weapon slot".to_string(),
             level_requirement: 28,
+            tags: vec!["arsenal".to_string()],
             attributes: Attributes {
                 attribute_type: "weapon_count".to_string(),
                 quantity: 1,
@@ -595,8 +694,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Huge Radius Enhancer".to_string(),
             price: 190,
+            rarity: Rarity::Uncommon,
             description: "Increases damage radius by 120".to_string(),
             level_requirement: 25,
+            tags: vec!["explosive".to_string()],
             attributes: Attributes {
                 attribute_type: "damage_radius".to_string(),
                 quantity: 120,
@@ -607,8 +708,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Giant Health Potion".to_string(),
             price: 70,
+            rarity: Rarity::Common,
             description: "Restores 350 health".to_string(),
             level_requirement: 10,
+            tags: Vec::new(),
             attributes: Attributes {
                 attribute_type: "player_health".to_string(),
                 quantity: 350,
@@ -619,8 +722,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Agility Boots".to_string(),
             price: 80,
+            rarity: Rarity::Common,
             description: "Increases movement speed by 15%".to_string(),
             level_requirement: 7,
+            tags: vec!["speed".to_string()],
             attributes: Attributes {
                 attribute_type: "movement_speed".to_string(),
                 quantity: 15,
@@ -631,8 +736,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Critical Amulet".to_string(),
             price: 155,
+            rarity: Rarity::Uncommon,
             description: "Increases critical chance by 15%".to_string(),
             level_requirement: 18,
+            tags: vec!["crit".to_string()],
             attributes: Attributes {
                 attribute_type: "critical_chance".to_string(),
                 quantity: 15,
@@ -643,8 +750,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Blades of Haste".to_string(),
             price: 115,
+            rarity: Rarity::Uncommon,
             description: "Increases attack speed by 25%".to_string(),
             level_requirement: 16,
+            tags: vec!["speed".to_string()],
             attributes: Attributes {
                 attribute_type: "attack_speed".to_string(),
                 quantity: 25,
@@ -655,8 +764,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Fortress Shield".to_string(),
             price: 90,
+            rarity: Rarity::Common,
             description: "Increases defense by 50".to_string(),
             level_requirement: 11,
+            tags: vec!["defense".to_string()],
             attributes: Attributes {
                 attribute_type: "defense".to_string(),
                 quantity: 50,
@@ -667,8 +778,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Sniper Ring".to_string(),
             price: 175,
+            rarity: Rarity::Uncommon,
             description: "Increases damage range by 400".to_string(),
             level_requirement: 22,
+            tags: vec!["precision".to_string()],
             attributes: Attributes {
                 attribute_type: "damage_range".to_string(),
                 quantity: 400,
@@ -679,8 +792,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Strength Elixir".to_string(),
             price: 140,
+            rarity: Rarity::Uncommon,
             description: "Increases weapon damage by 45".to_string(),
             level_requirement: 17,
+            tags: vec!["arsenal".to_string()],
             attributes: Attributes {
                 attribute_type: "weapon_damage".to_string(),
                 quantity: 45,
@@ -691,8 +806,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Rapid Bullets".to_string(),
             price: 135,
+            rarity: Rarity::Uncommon,
             description: "Increases bullet speed by 150".to_string(),
             level_requirement: 15,
+            tags: vec!["speed".to_string()],
             attributes: Attributes {
                 attribute_type: "bullet_speed".to_string(),
                 quantity: 150,
@@ -703,8 +820,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Fourth Weapon Slot".to_string(),
             price: 230,
+            rarity: Rarity::Rare,
             description: "Adds 1 additional weapon slot".to_string(),
             level_requirement: 35,
+            tags: vec!["arsenal".to_string()],
             attributes: Attributes {
                 attribute_type: "weapon_count".to_string(),
                 quantity: 1,
@@ -715,8 +834,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Giant Radius Booster".to_string(),
             price: 200,
+            rarity: Rarity::Rare,
             description: "Increases damage radius by 150".to_string(),
             level_requirement: 30,
+            tags: vec!["explosive".to_string()],
             attributes: Attributes {
                 attribute_type: "damage_radius".to_string(),
                 quantity: 150,
@@ -727,8 +848,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Ultimate Health Potion".to_string(),
             price: 85,
+            rarity: Rarity::Common,
             description: "Restores 400 health".to_string(),
             level_requirement: 12,
+            tags: Vec::new(),
             attributes: Attributes {
                 attribute_type: "player_health".to_string(),
                 quantity: 400,
@@ -739,8 +862,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Speedy Boots".to_string(),
             price: 95,
+            rarity: Rarity::Common,
             description: "Increases movement speed by 20%".to_string(),
             level_requirement: 12,
+            tags: vec!["speed".to_string()],
             attributes: Attributes {
                 attribute_type: "movement_speed".to_string(),
                 quantity: 20,
@@ -751,8 +876,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Critical Wristband".to_string(),
             price: 165,
+            rarity: Rarity::Uncommon,
             description: "Increases critical chance by 18%".to_string(),
             level_requirement: 20,
+            tags: vec!["crit".to_string()],
             attributes: Attributes {
                 attribute_type: "critical_chance".to_string(),
                 quantity: 18,
@@ -763,8 +890,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Blades of Agility".to_string(),
             price: 120,
+            rarity: Rarity::Uncommon,
             description: "Increases attack speed by 26%".to_string(),
             level_requirement: 18,
+            tags: vec!["speed".to_string()],
             attributes: Attributes {
                 attribute_type: "attack_speed".to_string(),
                 quantity: 26,
@@ -775,8 +904,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Elite Shield".to_string(),
             price: 95,
+            rarity: Rarity::Common,
             description: "Increases defense by 55".to_string(),
             level_requirement: 13,
+            tags: vec!["defense".to_string()],
             attributes: Attributes {
                 attribute_type: "defense".to_string(),
                 quantity: 55,
@@ -787,8 +918,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Damage Scope".to_string(),
             price: 180,
+            rarity: Rarity::Uncommon,
             description: "Increases damage range by 500".to_string(),
             level_requirement: 24,
+            tags: vec!["precision".to_string()],
             attributes: Attributes {
                 attribute_type: "damage_range".to_string(),
                 quantity: 500,
@@ -799,8 +932,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Power Boost".to_string(),
             price: 150,
+            rarity: Rarity::Uncommon,
             description: "Increases weapon damage by 50".to_string(),
             level_requirement: 20,
+            tags: vec!["arsenal".to_string()],
             attributes: Attributes {
                 attribute_type: "weapon_damage".to_string(),
                 quantity: 50,
@@ -811,8 +946,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Ultra Bullets".to_string(),
             price: 140,
+            rarity: Rarity::Uncommon,
             description: "Increases bullet speed by 170".to_string(),
             level_requirement: 18,
+            tags: vec!["speed".to_string()],
             attributes: Attributes {
                 attribute_type: "bullet_speed".to_string(),
                 quantity: 170,
@@ -823,8 +960,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Fifth Weapon Slot".to_string(),
             price: 270,
+            rarity: Rarity::Rare,
             description: "Adds 1 additional weapon slot".to_string(),
             level_requirement: 40,
+            tags: vec!["arsenal".to_string()],
             attributes: Attributes {
                 attribute_type: "weapon_count".to_string(),
                 quantity: 1,
@@ -835,8 +974,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Colossal Radius Booster".to_string(),
             price: 220,
+            rarity: Rarity::Rare,
             description: "Increases damage radius by 150".to_string(),
             level_requirement: 35,
+            tags: vec!["explosive".to_string()],
             attributes: Attributes {
                 attribute_type: "damage_radius".to_string(),
                 quantity: 150,
@@ -847,8 +988,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Super Health Potion".to_string(),
             price: 100,
+            rarity: Rarity::Uncommon,
             description: "Restores 500 health".to_string(),
             level_requirement: 15,
+            tags: Vec::new(),
             attributes: Attributes {
                 attribute_type: "player_health".to_string(),
                 quantity: 500,
@@ -859,8 +1002,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Legendary Boots".to_string(),
             price: 105,
+            rarity: Rarity::Uncommon,
             description: "Increases movement speed by 25%".to_string(),
             level_requirement: 15,
+            tags: vec!["speed".to_string()],
             attributes: Attributes {
                 attribute_type: "movement_speed".to_string(),
                 quantity: 25,
@@ -871,8 +1016,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Critical Band".to_string(),
             price: 175,
+            rarity: Rarity::Uncommon,
             description: "Increases critical chance by 20%".to_string(),
             level_requirement: 22,
+            tags: vec!["crit".to_string()],
             attributes: Attributes {
                 attribute_type: "critical_chance".to_string(),
                 quantity: 20,
@@ -883,8 +1030,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Super Blades".to_string(),
             price: 130,
+            rarity: Rarity::Uncommon,
             description: "Increases attack speed by 30%".to_string(),
             level_requirement: 20,
+            tags: vec!["speed".to_string()],
             attributes: Attributes {
                 attribute_type: "attack_speed".to_string(),
                 quantity: 30,
@@ -895,8 +1044,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Diamond Shield".to_string(),
             price: 110,
+            rarity: Rarity::Uncommon,
             description: "Increases defense by 60".to_string(),
             level_requirement: 16,
+            tags: vec!["defense".to_string()],
             attributes: Attributes {
                 attribute_type: "defense".to_string(),
                 quantity: 60,
@@ -907,8 +1058,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Ultimate Range Scope".to_string(),
             price: 200,
+            rarity: Rarity::Rare,
             description: "Increases damage range by 600".to_string(),
             level_requirement: 28,
+            tags: vec!["precision".to_string()],
             attributes: Attributes {
                 attribute_type: "damage_range".to_string(),
                 quantity: 600,
@@ -919,8 +1072,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Ultimate Damage Boost".to_string(),
             price: 175,
+            rarity: Rarity::Uncommon,
             description: "Increases weapon damage by 60".to_string(),
             level_requirement: 22,
+            tags: vec!["arsenal".to_string()],
             attributes: Attributes {
                 attribute_type: "weapon_damage".to_string(),
                 quantity: 60,
@@ -931,8 +1086,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Hyper Bullets".to_string(),
             price: 155,
+            rarity: Rarity::Uncommon,
             description: "Increases bullet speed by 200".to_string(),
             level_requirement: 20,
+            tags: vec!["speed".to_string()],
             attributes: Attributes {
                 attribute_type: "bullet_speed".to_string(),
                 quantity: 200,
@@ -943,8 +1100,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Sixth Weapon Slot".to_string(),
             price: 300,
+            rarity: Rarity::Rare,
             description: "Adds 1 additional weapon slot".to_string(),
             level_requirement: 45,
+            tags: vec!["arsenal".to_string()],
             attributes: Attributes {
                 attribute_type: "weapon_count".to_string(),
                 quantity: 1,
@@ -955,8 +1114,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Ultimate Radius Booster".to_string(),
             price: 250,
+            rarity: Rarity::Rare,
             description: "Increases damage radius by 150".to_string(),
             level_requirement: 40,
+            tags: vec!["explosive".to_string()],
             attributes: Attributes {
                 attribute_type: "damage_radius".to_string(),
                 quantity: 150,
@@ -967,8 +1128,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Mega Health Potion".to_string(),
             price: 120,
+            rarity: Rarity::Uncommon,
             description: "Restores 600 health".to_string(),
             level_requirement: 18,
+            tags: Vec::new(),
             attributes: Attributes {
                 attribute_type: "player_health".to_string(),
                 quantity: 600,
@@ -979,8 +1142,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Ultimate Boots".to_string(),
             price: 130,
+            rarity: Rarity::Uncommon,
             description: "Increases movement speed by 30%".to_string(),
             level_requirement: 20,
+            tags: vec!["speed".to_string()],
             attributes: Attributes {
                 attribute_type: "movement_speed".to_string(),
                 quantity: 30,
@@ -991,8 +1156,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Legendary Critical Ring".to_string(),
             price: 190,
+            rarity: Rarity::Uncommon,
             description: "Increases critical chance by 25%".to_string(),
             level_requirement: 25,
+            tags: vec!["crit".to_string()],
             attributes: Attributes {
                 attribute_type: "critical_chance".to_string(),
                 quantity: 25,
@@ -1003,8 +1170,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Legendary Blades".to_string(),
             price: 145,
+            rarity: Rarity::Uncommon,
             description: "Increases attack speed by 35%".to_string(),
             level_requirement: 22,
+            tags: vec!["speed".to_string()],
             attributes: Attributes {
                 attribute_type: "attack_speed".to_string(),
                 quantity: 35,
@@ -1015,8 +1184,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Legendary Shield".to_string(),
             price: 125,
+            rarity: Rarity::Uncommon,
             description: "Increases defense by 70".to_string(),
             level_requirement: 20,
+            tags: vec!["defense".to_string()],
             attributes: Attributes {
                 attribute_type: "defense".to_string(),
                 quantity: 70,
@@ -1027,8 +1198,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Legendary Range Scope".to_string(),
             price: 225,
+            rarity: Rarity::Rare,
             description: "Increases damage range by 800".to_string(),
             level_requirement: 30,
+            tags: vec!["precision".to_string()],
             attributes: Attributes {
                 attribute_type: "damage_range".to_string(),
                 quantity: 800,
@@ -1039,8 +1212,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Legendary Damage Boost".to_string(),
             price: 200,
+            rarity: Rarity::Rare,
             description: "Increases weapon damage by 75".to_string(),
             level_requirement: 28,
+            tags: vec!["arsenal".to_string()],
             attributes: Attributes {
                 attribute_type: "weapon_damage".to_string(),
                 quantity: 75,
@@ -1051,8 +1226,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Legendary Bullets".to_string(),
             price: 175,
+            rarity: Rarity::Uncommon,
             description: "Increases bullet speed by 250".to_string(),
             level_requirement: 25,
+            tags: vec!["speed".to_string()],
             attributes: Attributes {
                 attribute_type: "bullet_speed".to_string(),
                 quantity: 250,
@@ -1063,8 +1240,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Legendary Weapon Slot".to_string(),
             price: 340,
+            rarity: Rarity::Rare,
             description: "Adds 1 additional weapon slot".to_string(),
             level_requirement: 50,
+            tags: vec!["arsenal".to_string()],
             attributes: Attributes {
                 attribute_type: "weapon_count".to_string(),
                 quantity: 1,
@@ -1075,8 +1254,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Legendary Radius Booster".to_string(),
             price: 300,
+            rarity: Rarity::Rare,
             description: "Increases damage radius by 200".to_string(),
             level_requirement: 45,
+            tags: vec!["explosive".to_string()],
             attributes: Attributes {
                 attribute_type: "damage_radius".to_string(),
                 quantity: 200,
@@ -1087,8 +1268,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Legendary Health Potion".to_string(),
             price: 150,
+            rarity: Rarity::Uncommon,
             description: "Restores 750 health".to_string(),
             level_requirement: 30,
+            tags: Vec::new(),
             attributes: Attributes {
                 attribute_type: "player_health".to_string(),
                 quantity: 750,
@@ -1099,8 +1282,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Legendary Boots".to_string(),
             price: 150,
+            rarity: Rarity::Uncommon,
             description: "Increases movement speed by 35%".to_string(),
             level_requirement: 30,
+            tags: vec!["speed".to_string()],
             attributes: Attributes {
                 attribute_type: "movement_speed".to_string(),
                 quantity: 35,
@@ -1111,8 +1296,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Speed Potion".to_string(),
             price: 60,
+            rarity: Rarity::Common,
             description: "Increases movement speed by 10%".to_string(),
             level_requirement: 5,
+            tags: vec!["speed".to_string()],
             attributes: Attributes {
                 attribute_type: "movement_speed".to_string(),
                 quantity: 10,
@@ -1123,8 +1310,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Critical Rune".to_string(),
             price: 130,
+            rarity: Rarity::Uncommon,
             description: "Increases critical chance by 12%".to_string(),
             level_requirement: 12,
+            tags: vec!["crit".to_string()],
             attributes: Attributes {
                 attribute_type: "critical_chance".to_string(),
                 quantity: 12,
@@ -1135,8 +1324,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Quick Blades".to_string(),
             price: 90,
+            rarity: Rarity::Common,
             description: "Increases attack speed by 18%".to_string(),
             level_requirement: 8,
+            tags: vec!["speed".to_string()],
             attributes: Attributes {
                 attribute_type: "attack_speed".to_string(),
                 quantity: 18,
@@ -1147,8 +1338,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Defense Shield".to_string(),
             price: 75,
+            rarity: Rarity::Common,
             description: "Increases defense by 25".to_string(),
             level_requirement: 6,
+            tags: vec!["defense".to_string()],
             attributes: Attributes {
                 attribute_type: "defense".to_string(),
                 quantity: 25,
@@ -1159,8 +1352,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Short Range Scope".to_string(),
             price: 120,
+            rarity: Rarity::Uncommon,
             description: "Increases damage range by 150".to_string(),
             level_requirement: 10,
+            tags: vec!["precision".to_string()],
             attributes: Attributes {
                 attribute_type: "damage_range".to_string(),
                 quantity: 150,
@@ -1171,8 +1366,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Damage Potion".to_string(),
             price: 110,
+            rarity: Rarity::Uncommon,
             description: "Increases weapon damage by 25".to_string(),
             level_requirement: 9,
+            tags: vec!["arsenal".to_string()],
             attributes: Attributes {
                 attribute_type: "weapon_damage".to_string(),
                 quantity: 25,
@@ -1183,8 +1380,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Fast Bullets".to_string(),
             price: 105,
+            rarity: Rarity::Uncommon,
             description: "Increases bullet speed by 90".to_string(),
             level_requirement: 8,
+            tags: vec!["speed".to_string()],
             attributes: Attributes {
                 attribute_type: "bullet_speed".to_string(),
                 quantity: 90,
@@ -1195,8 +1394,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Extra Slot".to_string(),
             price: 145,
+            rarity: Rarity::Uncommon,
             description: "Adds an additional weapon slot".to_string(),
             level_requirement: 12,
+            tags: vec!["arsenal".to_string()],
             attributes: Attributes {
                 attribute_type: "weapon_count".to_string(),
                 quantity: 1,
@@ -1207,8 +1408,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Small Radius Booster".to_string(),
             price: 70,
+            rarity: Rarity::Common,
             description: "Increases damage radius by 50".to_string(),
             level_requirement: 5,
+            tags: vec!["explosive".to_string()],
             attributes: Attributes {
                 attribute_type: "damage_radius".to_string(),
                 quantity: 50,
@@ -1219,8 +1422,10 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Major Health Potion".to_string(),
             price: 55,
+            rarity: Rarity::Common,
             description: "Restores 150 health".to_string(),
             level_requirement: 4,
+            tags: Vec::new(),
             attributes: Attributes {
                 attribute_type: "player_health".to_string(),
                 quantity: 150,
@@ -1231,24 +1436,262 @@ pub fn shop_items() -> Vec<Item> {
             texture_name: "love_potion".to_string(),
             name: "Nuclear ammo".to_string(),
             price: 5000,
+            rarity: Rarity::Legendary,
             description: "Increases AoE targets by 1".to_string(),
             level_requirement: 10,
+            tags: vec!["explosive".to_string()],
             attributes: Attributes {
                 attribute_type: "aoe_targets".to_string(),
                 quantity: 1,
                 duration: 0,
             },
+        },
+        Item {
+            texture_name: "love_potion".to_string(),
+            name: "Pocket Magnet".to_string(),
+            price: 40,
+            rarity: Rarity::Common,
+            description: "Increases pickup radius by 25".to_string(),
+            level_requirement: 1,
+            tags: vec!["greed".to_string()],
+            attributes: Attributes {
+                attribute_type: "pickup_radius".to_string(),
+                quantity: 25,
+                duration: 0,
+            },
+        },
+        Item {
+            texture_name: "love_potion".to_string(),
+            name: "Lodestone".to_string(),
+            price: 95,
+            rarity: Rarity::Common,
+            description: "Increases pickup radius by 60".to_string(),
+            level_requirement: 6,
+            tags: vec!["greed".to_string()],
+            attributes: Attributes {
+                attribute_type: "pickup_radius".to_string(),
+                quantity: 60,
+                duration: 0,
+            },
+        },
+        Item {
+            texture_name: "love_potion".to_string(),
+            name: "Gravity Well".to_string(),
+            price: 260,
+            rarity: Rarity::Rare,
+            description: "Increases pickup radius by 150".to_string(),
+            level_requirement: 15,
+            tags: vec!["greed".to_string()],
+            attributes: Attributes {
+                attribute_type: "pickup_radius".to_string(),
+                quantity: 150,
+                duration: 0,
+            },
+        },
+        Item {
+            texture_name: "love_potion".to_string(),
+            name: "Greased Pockets".to_string(),
+            price: 35,
+            rarity: Rarity::Common,
+            description: "Increases pickup speed by 50".to_string(),
+            level_requirement: 1,
+            tags: vec!["greed".to_string()],
+            attributes: Attributes {
+                attribute_type: "pickup_speed".to_string(),
+                quantity: 50,
+                duration: 0,
+            },
+        },
+        Item {
+            texture_name: "love_potion".to_string(),
+            name: "Collector's Glove".to_string(),
+            price: 85,
+            rarity: Rarity::Common,
+            description: "Increases pickup speed by 120".to_string(),
+            level_requirement: 7,
+            tags: vec!["greed".to_string()],
+            attributes: Attributes {
+                attribute_type: "pickup_speed".to_string(),
+                quantity: 120,
+                duration: 0,
+            },
+        },
+        Item {
+            texture_name: "love_potion".to_string(),
+            name: "Lucky Coin".to_string(),
+            price: 15,
+            rarity: Rarity::Common,
+            description: "Grants 1 free reroll".to_string(),
+            level_requirement: 1,
+            tags: Vec::new(),
+            attributes: Attributes {
+                attribute_type: "free_reroll".to_string(),
+                quantity: 1,
+                duration: 0,
+            },
+        },
+        Item {
+            texture_name: "love_potion".to_string(),
+            name: "Gambler's Dice".to_string(),
+            price: 40,
+            rarity: Rarity::Common,
+            description: "Grants 3 free rerolls".to_string(),
+            level_requirement: 5,
+            tags: Vec::new(),
+            attributes: Attributes {
+                attribute_type: "free_reroll".to_string(),
+                quantity: 3,
+                duration: 0,
+            },
         }
     ];
-    items
+    // Some items have to be unlocked with tokens between runs before they can be found
+    items.into_iter().filter(|item| profile::item_available(&item.name)).collect()
+}
+
+pub fn get_four_items_from_list(max_level: u32, wave: u32) -> Vec<Item> {
+    roll_items(max_level, wave, None)
+}
+
+// Same as a normal roll but at least one of the four items is of the given rarity or better
+pub fn get_four_items_with_guarantee(max_level: u32, wave: u32, minimum: Rarity) -> Vec<Item> {
+    roll_items(max_level, wave, Some(minimum))
+}
+
+// Replaces every unlocked slot with a freshly rolled item, locked items keep their slot
+pub fn reroll_unlocked(items: &mut Vec<Item>, locked_slots: &mut Vec<bool>, rolled: Vec<Item>) {
+    let mut rolled = rolled.into_iter()
+        .filter(|new_item| !items.iter().zip(locked_slots.iter()).any(|(item, locked)| *locked && item.name == new_item.name));
+    let mut new_items = Vec::new();
+    let mut new_locks = Vec::new();
+    for i in 0..4 {
+        if i < items.len() && locked_slots[i] {
+            new_items.push(items[i].clone());
+            new_locks.push(true);
+        } else if let Some(item) = rolled.next() {
+            new_items.push(item);
+            new_locks.push(false);
+        }
+    }
+    *items = new_items;
+    *locked_slots = new_locks;
+}
+
+// True when the pool at this level holds anything of the rarity or better, otherwise a guaranteed roll can't keep its promise
+pub fn guarantee_available(max_level: u32, minimum: Rarity) -> bool {
+    eligible_items(max_level).iter().any(|item| item.rarity >= minimum)
 }
 
-pub fn get_four_items_from_list(mut max_level: u32) -> Vec<Item> {
-    // Filter items within the specified level range
+// Items within the level range that can show up in the shop
+fn eligible_items(mut max_level: u32) -> Vec<Item> {
     if max_level < 1 {
         max_level = 1
     }
     let mut items = shop_items();
     items.retain(|item| item.level_requirement <= max_level);
-    utilities::get_random_elements(&mut items, 4)
-}
\ No newline at end of file
+    items
+}
+
+fn roll_items(max_level: u32, wave: u32, guarantee: Option<Rarity>) -> Vec<Item> {
+    let mut items = eligible_items(max_level);
+
+    let weights = Rarity::weights(wave, max_level);
+    let mut rolled: Vec<Item> = Vec::new();
+    if let Some(minimum) = guarantee {
+        if let Some(item) = take_item(&mut items, &weights, minimum) {
+            rolled.push(item);
+        }
+    }
+    while rolled.len() < 4 {
+        match take_item(&mut items, &weights, Rarity::Common) {
+            Some(item) => rolled.push(item),
+            None => break,
+        }
+    }
+    utilities::get_random_elements(&rolled, rolled.len())
+}
+
+// Rolls a rarity no lower than the minimum and removes a random item of it from the pool.
+// Rarities with nothing left in the pool are left out of the roll, their weight goes to the others.
+fn take_item(items: &mut Vec<Item>, weights: &[f32; 5], minimum: Rarity) -> Option<Item> {
+    let allowed: Vec<(Rarity, f32)> = Rarity::ALL.iter().zip(weights.iter())
+        .filter(|(rarity, _)| **rarity >= minimum && items.iter().any(|item| item.rarity == **rarity))
+        .map(|(rarity, weight)| (*rarity, *weight))
+        .collect();
+    let total: f32 = allowed.iter().map(|(_, weight)| weight).sum();
+    if allowed.is_empty() || total <= 0.0 {
+        return None;
+    }
+
+    let mut roll = utilities::random_float(0.0..total);
+    let mut rarity = allowed[allowed.len() - 1].0;
+    for (candidate, weight) in allowed.iter() {
+        if roll < *weight {
+            rarity = *candidate;
+            break;
+        }
+        roll -= weight;
+    }
+
+    let candidates: Vec<usize> = items.iter().enumerate()
+        .filter(|(_, item)| item.rarity == rarity)
+        .map(|(i, _)| i)
+        .collect();
+    let index = candidates[utilities::random_number(0..=(candidates.len() as i32 - 1)) as usize];
+    Some(items.remove(index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(name: &str) -> Item {
+        Item {
+            texture_name: "love_potion".to_string(),
+            name: name.to_string(),
+            price: 50,
+            rarity: Rarity::Common,
+            description: String::new(),
+            level_requirement: 1,
+            tags: Vec::new(),
+            attributes: Attributes { attribute_type: "damage".to_string(), quantity: 1, duration: 0 },
+        }
+    }
+
+    fn names(items: &[Item]) -> Vec<&str> {
+        items.iter().map(|item| item.name.as_str()).collect()
+    }
+
+    #[test]
+    fn locked_slots_survive_a_reroll_in_place() {
+        let mut items = vec![item("A"), item("B"), item("C"), item("D")];
+        let mut locked_slots = vec![false, true, false, true];
+        reroll_unlocked(&mut items, &mut locked_slots, vec![item("E"), item("F"), item("G"), item("H")]);
+        assert_eq!(names(&items), vec!["E", "B", "F", "D"]);
+        assert_eq!(locked_slots, vec![false, true, false, true]);
+    }
+
+    #[test]
+    fn a_reroll_never_duplicates_a_locked_item() {
+        let mut items = vec![item("A"), item("B"), item("C"), item("D")];
+        let mut locked_slots = vec![true, false, false, false];
+        reroll_unlocked(&mut items, &mut locked_slots, vec![item("A"), item("E"), item("F"), item("G"), item("H")]);
+        assert_eq!(names(&items), vec!["A", "E", "F", "G"]);
+        assert_eq!(locked_slots, vec![true, false, false, false]);
+    }
+
+    #[test]
+    fn guarantee_needs_an_eligible_item_of_the_rarity() {
+        assert!(guarantee_available(1, Rarity::Rare));
+        assert!(!guarantee_available(1, Rarity::Epic));
+        assert!(guarantee_available(20, Rarity::Epic));
+    }
+
+    #[test]
+    fn guaranteed_roll_includes_the_minimum_rarity() {
+        for _ in 0..20 {
+            let items = get_four_items_with_guarantee(20, 1, Rarity::Epic);
+            assert!(items.iter().any(|item| item.rarity >= Rarity::Epic));
+        }
+    }
+}
@@ -1,6 +1,32 @@
 use macroquad::prelude::*;
 use crate::player::Player;
-use crate::shop::Item;
+use crate::shop::{Attributes, Item};
+use crate::shop::synergy;
+use crate::events::{self, GameEvent};
+
+// Fraction of the price refunded when an item is sold back
+pub const SELL_RATE: f32 = 0.5;
+pub const MAX_WEAPON_COUNT: usize = 6;
+
+// A single stat as it is now and as it would be after buying an item
+pub struct StatPreview {
+    pub label: String,
+    pub current: String,
+    pub new: String,
+    // The purchase would push the stat past its cap so part of it is wasted
+    pub capped: bool,
+}
+
+impl StatPreview {
+    fn new(label: &str, current: f32, new: f32, precision: usize) -> Self {
+        StatPreview {
+            label: label.to_string(),
+            current: format!("{:.*}", precision, current),
+            new: format!("{:.*}", precision, new),
+            capped: false,
+        }
+    }
+}
 
 pub(crate) struct ApplyShopItem {
     pub item: Item,
@@ -15,6 +41,23 @@ impl ApplyShopItem {
         }
     }
 
+    pub fn sell_price(item: &Item) -> u32 {
+        (item.price as f32 * SELL_RATE) as u32
+    }
+
+    // Refunds part of the price and takes the item's stats away again
+    pub fn sell_item(&self, player: &mut Player) {
+        if let Some(applied) = player.inventory.remove(&self.item.name) {
+            let refund = ApplyShopItem::sell_price(&self.item);
+            player.stats.money += refund;
+            player.run_stats.record_refund(refund);
+            // Only take back what the copy actually gave, a capped weapon count may have gained less
+            let attributes = Attributes { quantity: applied, ..self.item.attributes.clone() };
+            ApplyShopItem::revert_attributes(&attributes, player);
+            synergy::refresh(player);
+        }
+    }
+
     fn apply_buff(&self, player: &mut Player) {
         /*match self.item.attributes.attribute_type.as_str() {
             "health" => player.health += self.item.attributes.quantity as f32,
@@ -26,26 +69,94 @@ impl ApplyShopItem {
 
     fn apply_item_attributes(&self, player: &mut Player) {
         player.stats.money -= self.item.price;
-        match self.item.attributes.attribute_type.as_str() {
-            "player_health" => player.stats.health += self.item.attributes.quantity as f32,
-            "defense" => player.stats.defense += self.item.attributes.quantity,
-            "damage_radius" => player.weapon.damage_radius += self.item.attributes.quantity as f32,
-            "damage_range" => player.weapon.range += self.item.attributes.quantity as f32,
-            "weapon_damage" => player.weapon.damage += self.item.attributes.quantity as f32,
+        events::emit(GameEvent::ItemPurchased { name: self.item.name.clone(), tags: self.item.tags.clone(), price: self.item.price });
+        let weapons_before = player.weapon.count;
+        ApplyShopItem::apply_attributes(&self.item.attributes, player);
+        if !self.item.is_consumable() {
+            let applied = if self.item.attributes.attribute_type == "weapon_count" {
+                (player.weapon.count - weapons_before) as u32
+            } else {
+                self.item.attributes.quantity
+            };
+            player.inventory.add(&self.item, applied);
+            synergy::refresh(player);
+        }
+    }
+
+    pub fn apply_attributes(attributes: &Attributes, player: &mut Player) {
+        match attributes.attribute_type.as_str() {
+            "player_health" => player.stats.health += attributes.quantity as f32,
+            "defense" => player.stats.defense += attributes.quantity,
+            "damage_radius" => player.weapon.damage_radius += attributes.quantity as f32,
+            "damage_range" => player.weapon.range += attributes.quantity as f32,
+            "weapon_damage" => player.weapon.damage += attributes.quantity as f32,
             "weapon_count" => {
-                if player.weapon.count + self.item.attributes.quantity as usize > 6 {
-                    player.weapon.count = 6;
+                if player.weapon.count + attributes.quantity as usize > MAX_WEAPON_COUNT {
+                    player.weapon.count = MAX_WEAPON_COUNT;
                 } else {
-                    player.weapon.count += self.item.attributes.quantity as usize;
+                    player.weapon.count += attributes.quantity as usize;
                 }
             }
-            "bullet_speed" => player.weapon.speed += self.item.attributes.quantity as f32,
-            "attack_speed" => player.stats.attack_speed_modifier += self.item.attributes.quantity as f32 / 100.0,
-            "movement_speed" => player.stats.movement_speed += self.item.attributes.quantity as f32,
-            "critical_chance" => player.stats.critical_chance += (self.item.attributes.quantity as f32 / 100.0),
-            "critical_damage" => player.stats.critical_damage += (self.item.attributes.quantity as f32 / 100.0),
-            "aoe_targets" => player.weapon.aoe_count += self.item.attributes.quantity as usize,
+            "bullet_speed" => player.weapon.speed += attributes.quantity as f32,
+            "attack_speed" => player.stats.attack_speed_modifier += attributes.quantity as f32 / 100.0,
+            "movement_speed" => player.stats.movement_speed += attributes.quantity as f32,
+            "critical_chance" => player.stats.critical_chance += attributes.quantity as f32 / 100.0,
+            "critical_damage" => player.stats.critical_damage += attributes.quantity as f32 / 100.0,
+            "aoe_targets" => player.weapon.aoe_count += attributes.quantity as usize,
+            "pickup_radius" => player.stats.pickup_radius += attributes.quantity as f32,
+            "pickup_speed" => player.stats.pickup_speed += attributes.quantity as f32,
+            "free_reroll" => player.stats.free_rerolls += attributes.quantity,
+            _ => {}
+        }
+    }
+
+    pub fn preview_attributes(attributes: &Attributes, player: &Player) -> Option<StatPreview> {
+        let quantity = attributes.quantity as f32;
+        let preview = match attributes.attribute_type.as_str() {
+            "player_health" => StatPreview::new("Health", player.stats.health, player.stats.health + quantity, 0),
+            "defense" => StatPreview::new("Defense", player.stats.defense as f32, player.stats.defense as f32 + quantity, 0),
+            "damage_radius" => StatPreview::new("Splash Radius", player.weapon.damage_radius, player.weapon.damage_radius + quantity, 0),
+            "damage_range" => StatPreview::new("Weapon Range", player.weapon.range, player.weapon.range + quantity, 0),
+            "weapon_damage" => StatPreview::new("Weapon Damage", player.weapon.damage, player.weapon.damage + quantity, 0),
+            "weapon_count" => {
+                let wanted = player.weapon.count + attributes.quantity as usize;
+                let mut preview = StatPreview::new("Weapon Count", player.weapon.count as f32, wanted.min(MAX_WEAPON_COUNT) as f32, 0);
+                preview.capped = wanted > MAX_WEAPON_COUNT;
+                preview
+            }
+            "bullet_speed" => StatPreview::new("Bullet Speed", player.weapon.speed, player.weapon.speed + quantity, 0),
+            "attack_speed" => {
+                let new_interval = 1.0 / (player.stats.base_attack_speed * (player.stats.attack_speed_modifier + quantity / 100.0));
+                StatPreview::new("Attack Interval", player.attack_interval(), new_interval, 2)
+            }
+            "movement_speed" => StatPreview::new("Movement Speed", player.stats.movement_speed, player.stats.movement_speed + quantity, 0),
+            "critical_chance" => StatPreview::new("Critical Chance %", player.stats.critical_chance * 100.0, player.stats.critical_chance * 100.0 + quantity, 1),
+            "critical_damage" => StatPreview::new("Critical Damage %", player.stats.critical_damage * 100.0, player.stats.critical_damage * 100.0 + quantity, 1),
+            "aoe_targets" => StatPreview::new("AoE Targets", player.weapon.aoe_count as f32, player.weapon.aoe_count as f32 + quantity, 0),
+            "pickup_radius" => StatPreview::new("Pickup Radius", player.stats.pickup_radius, player.stats.pickup_radius + quantity, 0),
+            "pickup_speed" => StatPreview::new("Pickup Speed", player.stats.pickup_speed, player.stats.pickup_speed + quantity, 0),
+            "free_reroll" => StatPreview::new("Free Rerolls", player.stats.free_rerolls as f32, player.stats.free_rerolls as f32 + quantity, 0),
+            _ => return None,
+        };
+        Some(preview)
+    }
+
+    pub fn revert_attributes(attributes: &Attributes, player: &mut Player) {
+        match attributes.attribute_type.as_str() {
+            "defense" => player.stats.defense = player.stats.defense.saturating_sub(attributes.quantity),
+            "damage_radius" => player.weapon.damage_radius -= attributes.quantity as f32,
+            "damage_range" => player.weapon.range -= attributes.quantity as f32,
+            "weapon_damage" => player.weapon.damage -= attributes.quantity as f32,
+            "weapon_count" => player.weapon.count = player.weapon.count.saturating_sub(attributes.quantity as usize).max(1),
+            "bullet_speed" => player.weapon.speed -= attributes.quantity as f32,
+            "attack_speed" => player.stats.attack_speed_modifier -= attributes.quantity as f32 / 100.0,
+            "movement_speed" => player.stats.movement_speed -= attributes.quantity as f32,
+            "critical_chance" => player.stats.critical_chance -= attributes.quantity as f32 / 100.0,
+            "critical_damage" => player.stats.critical_damage -= attributes.quantity as f32 / 100.0,
+            "aoe_targets" => player.weapon.aoe_count = player.weapon.aoe_count.saturating_sub(attributes.quantity as usize),
+            "pickup_radius" => player.stats.pickup_radius -= attributes.quantity as f32,
+            "pickup_speed" => player.stats.pickup_speed -= attributes.quantity as f32,
             _ => {}
         }
     }
-}
\ No newline at end of file
+}
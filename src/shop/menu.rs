@@ -2,51 +2,137 @@ use macroquad::prelude::*;
 use crate::player::Player;
 use crate::shop::*;
 use crate::shop::logic::ApplyShopItem;
+use crate::navigation::{self, Navigation};
 use crate::utilities;
 
-pub(crate) async fn draw_shop(shop_items: &mut Vec<Item>, player: &mut Player, shop_textures: &ShopTextures, shop: &mut Shop) {
-    loop {
-        clear_background(DARKGRAY);
-        player_money(&player, &shop_textures.get_texture("money_notes").unwrap());
+// The guaranteed reroll always offers at least one item of this rarity, at a multiple of the reroll cost
+const GUARANTEED_RARITY: Rarity = Rarity::Rare;
+const GUARANTEED_REROLL_MULTIPLIER: u32 = 3;
+const CARD_HEIGHT: f32 = 200.0;
 
-        if reroll_button(Vec2::new(10.0, 10.0), &shop_textures.get_texture("money_notes").unwrap(), &player, &shop) {
-            *shop_items = items::get_four_items_from_list(player.stats.level.level);
+// Draws one frame of the shop, returns true when the player wants to start the next wave
+pub(crate) fn draw_shop(shop_items: &mut Vec<Item>, locked_slots: &mut Vec<bool>, player: &mut Player, shop_textures: &ShopTextures, shop: &mut Shop, wave: u32, nav: &mut Navigation) -> bool {
+    locked_slots.resize(shop_items.len(), false);
+    clear_background(DARKGRAY);
+    player_money(&player, &shop_textures.get_texture("money_notes").unwrap());
+
+    if reroll_button(Vec2::new(10.0, 10.0), &shop_textures.get_texture("money_notes").unwrap(), &player, &shop, nav) {
+        let rolled = items::get_four_items_from_list(player.stats.level.level, wave);
+        items::reroll_unlocked(shop_items, locked_slots, rolled);
+        // Free rerolls are used up first and don't raise the cost
+        if player.stats.free_rerolls > 0 {
+            player.stats.free_rerolls -= 1;
+        } else {
             player.stats.money -= shop.reroll_cost;
+            player.run_stats.record_spent(shop.reroll_cost);
             shop.increment_reroll_cost();
         }
+    }
 
-        listings(shop_items, &shop_textures, player);
-        player_attributes_panel(&player);
+    let guarantee_available = items::guarantee_available(player.stats.level.level, GUARANTEED_RARITY);
+    if guaranteed_reroll_button(Vec2::new(10.0, 60.0), shop_textures.get_texture("money_notes").unwrap(), player, shop, guarantee_available, nav) {
+        let rolled = items::get_four_items_with_guarantee(player.stats.level.level, wave, GUARANTEED_RARITY);
+        items::reroll_unlocked(shop_items, locked_slots, rolled);
+        player.stats.money -= shop.reroll_cost * GUARANTEED_REROLL_MULTIPLIER;
+        player.run_stats.record_spent(shop.reroll_cost * GUARANTEED_REROLL_MULTIPLIER);
+        shop.increment_reroll_cost();
+    }
 
-        if start_next_wave() {
-            break;
-        }
+    listings(shop_items, locked_slots, shop_textures, player, nav);
+    player_attributes_panel(&player);
+    income_panel(&shop.income);
+    inventory_panel(player, nav);
 
-        next_frame().await;
-    }
+    start_next_wave(nav)
 }
 
-fn listings(displayed_items: &mut Vec<Item>, shop_textures: &ShopTextures, player: &mut Player) {
+fn listings(displayed_items: &mut Vec<Item>, locked_slots: &mut Vec<bool>, shop_textures: &ShopTextures, player: &mut Player, nav: &mut Navigation) {
     let mut remove_index = None;
+    let mut hovered_item = None;
+    let mut focused_item = None;
     let card_gap = 15.0;
     let card_width = screen_width() / 5.0;
     for (i, item) in displayed_items.iter().enumerate() {
         let card_x = 40.0 + i as f32 *  (card_width + card_gap) - card_gap;
         let card_y = screen_height() / 2.0 - 80.0;
         listing(&item, Vec2::new(card_x, card_y), &shop_textures.get_texture(&item.texture_name).unwrap(), card_width, &player);
-        if buy_button(Vec2::new(card_x + 300.0, card_y + 130.0), &shop_textures.get_texture("buy_button").unwrap(), &player, item.price) {
+        if utilities::hovering_over(Vec2::new(card_x, card_y), Vec2::new(card_width, CARD_HEIGHT)) {
+            hovered_item = Some(i);
+        }
+        // The whole card takes the focus, activating it presses the buy button
+        let (focused, activated) = nav.widget(buy_button(Vec2::new(card_x + 300.0, card_y + 130.0), &shop_textures.get_texture("buy_button").unwrap()));
+        if focused {
+            navigation::draw_focus(Vec2::new(card_x, card_y), Vec2::new(card_width, CARD_HEIGHT));
+            focused_item = Some((i, Vec2::new(card_x, card_y + CARD_HEIGHT + 10.0)));
+        }
+        if activated && can_purchase_item(item.price, player) {
             purchase_item(&item, player);
             remove_index = Some(i);
         }
+        if lock_button(Vec2::new(card_x + card_width - 90.0, card_y + 10.0), locked_slots[i]) || (focused && nav.secondary()) {
+            locked_slots[i] = !locked_slots[i];
+        }
+    }
+
+    // Drawn last so it sits on top of the neighbouring cards
+    if let Some(index) = hovered_item {
+        let (mouse_x, mouse_y) = mouse_position();
+        stat_tooltip(&displayed_items[index], player, Vec2::new(mouse_x + 16.0, mouse_y + 16.0));
+    } else if let Some((index, position)) = focused_item {
+        stat_tooltip(&displayed_items[index], player, position);
     }
 
     if let Some(index) = remove_index {
         displayed_items.remove(index);
+        locked_slots.remove(index);
+    }
+}
+
+fn stat_tooltip(item: &Item, player: &Player, position: Vec2) {
+    let Some(preview) = ApplyShopItem::preview_attributes(&item.attributes, player) else {
+        return;
+    };
+    let synergies = synergy::unlocked_by(item, player);
+    let mut lines: Vec<(String, Color)> = Vec::new();
+    let color = if preview.capped { GRAY } else { BLACK };
+    lines.push((format!("{}: {} -> {}", preview.label, preview.current, preview.new), color));
+    if preview.capped {
+        lines.push(("Already at the maximum".to_string(), GRAY));
+    }
+    for description in synergies {
+        lines.push((format!("Unlocks {}", description), DARKGREEN));
     }
+
+    let font_size = 20.0;
+    let line_height = 24.0;
+    let width = lines.iter()
+        .map(|(text, _)| measure_text(text, None, font_size as u16, 1.0).width)
+        .fold(0.0, f32::max) + 20.0;
+    let height = lines.len() as f32 * line_height + 12.0;
+    // Keep the tooltip on screen near the right edge
+    let x = position.x.min(screen_width() - width - 5.0);
+    let y = position.y;
+
+    draw_rectangle(x - 2.0, y - 2.0, width + 4.0, height + 4.0, BLACK);
+    draw_rectangle(x, y, width, height, Color::new(1.0, 0.98, 0.85, 1.0));
+    for (i, (text, color)) in lines.iter().enumerate() {
+        draw_text(text, x + 10.0, y + 24.0 + i as f32 * line_height, font_size, *color);
+    }
+}
+
+fn lock_button(position: Vec2, locked: bool) -> bool {
+    let button_dim = Vec2::new(80.0, 26.0);
+    let text = if locked { "LOCKED" } else { "LOCK" };
+    let is_hovered = utilities::hovering_over(position, button_dim);
+    let button_color = if locked { GOLD } else if is_hovered { GRAY } else { LIGHTGRAY };
+    draw_rectangle(position.x, position.y, button_dim.x, button_dim.y, button_color);
+    let text_pos = utilities::center_text(text, 20, button_dim, position);
+    draw_text(text, text_pos.x, text_pos.y, 20.0, BLACK);
+    is_hovered && is_mouse_button_pressed(MouseButton::Left)
 }
 
 pub fn listing(item: &Item, position: Vec2, texture: &Texture2D, card_width: f32, player: &Player) {
-    let card_height = 200.0;
+    let card_height = CARD_HEIGHT;
     let padding = 10.0;
 
     let texture_size = Vec2::new(75.0, 75.0);
@@ -57,8 +143,8 @@ pub fn listing(item: &Item, position: Vec2, texture: &Texture2D, card_width: f32
     let mut background_color = if is_hovered {  LIGHTGRAY } else { WHITE };
     background_color = if is_hovered && !can_purchase { Color::new(1.0, 0.678, 0.643, 1.00) } else { background_color };
 
-    // Draw card background with a border
-    draw_rectangle(position.x - 5.0, position.y - 5.0, card_width + 10.0, card_height + 10.0, BLACK);
+    // Draw card background with a border in the item's rarity color
+    draw_rectangle(position.x - 5.0, position.y - 5.0, card_width + 10.0, card_height + 10.0, item.rarity.color());
     draw_rectangle(position.x, position.y, card_width, card_height, background_color);
 
     draw_texture_ex(
@@ -77,13 +163,15 @@ pub fn listing(item: &Item, position: Vec2, texture: &Texture2D, card_width: f32
     draw_text(&item.description, text_x, position.y + 70.0, 18.0, GRAY);
     draw_text(&format!("Price: {} coins", item.price), text_x, position.y + 100.0, 18.0, DARKGRAY);
     draw_text(&format!("Level: {}", item.level_requirement), text_x, position.y + 120.0, 18.0, DARKGRAY);
+    draw_text(item.rarity.name(), text_x, position.y + 140.0, 18.0, item.rarity.color());
 }
 
 pub fn player_attributes_panel(player: &Player) {
+    let synergies = synergy::progress(player);
     let x  = screen_width() - 260.0;
     let y = 10.0;
     let panel_width = 250.0;
-    let panel_height = 500.0;
+    let panel_height = 530.0 + if synergies.is_empty() { 0.0 } else { 40.0 + synergies.len() as f32 * 45.0 };
     let padding = 20.0;
     let mut y_offset = y + padding;
 
@@ -118,21 +206,120 @@ pub fn player_attributes_panel(player: &Player) {
     draw_text(&format!("Weapon range: {}", player.weapon.range), x + padding, y_offset, 20.0, BLACK);
     y_offset += 30.0;
     draw_text(&format!("AoE Targets: {}", player.weapon.aoe_count), x + padding, y_offset, 20.0, BLACK);
+    y_offset += 30.0;
+    draw_text(&format!("Pickup Radius: {}", player.stats.pickup_radius), x + padding, y_offset, 20.0, BLACK);
+    y_offset += 30.0;
+    draw_text(&format!("Pickup Speed: {}", player.stats.pickup_speed), x + padding, y_offset, 20.0, BLACK);
+
+    if synergies.is_empty() {
+        return;
+    }
+    y_offset += 45.0;
+    draw_text("Synergies", x + padding, y_offset, 26.0, BLACK);
+    for progress in synergies.iter() {
+        y_offset += 25.0;
+        let color = if progress.active_tiers > 0 { DARKGREEN } else { DARKGRAY };
+        draw_text(&format!("{} (tier {})", progress.name, progress.active_tiers), x + padding, y_offset, 20.0, color);
+        y_offset += 20.0;
+        let next = match &progress.next {
+            Some((required, description)) => format!("{}/{}: {}", progress.owned, required, description),
+            None => format!("{} owned, max tier", progress.owned),
+        };
+        draw_text(&next, x + padding + 10.0, y_offset, 16.0, GRAY);
+    }
+}
+
+fn inventory_panel(player: &mut Player, nav: &mut Navigation) {
+    let x = 10.0;
+    let y = 120.0;
+    let panel_width = 300.0;
+    let row_height = 28.0;
+    let padding = 15.0;
+    // Stop above the income panel
+    let max_rows = ((screen_height() - 200.0 - y - 50.0) / row_height).max(0.0) as usize;
+    let rows = player.inventory.entries.len().min(max_rows);
+    let panel_height = 50.0 + rows as f32 * row_height;
+
+    draw_rectangle(x - 5.0, y - 5.0, panel_width + 10.0, panel_height + 10.0, BLACK);
+    draw_rectangle(x, y, panel_width, panel_height, WHITE);
+    draw_text("Inventory", x + padding, y + 30.0, 30.0, BLACK);
+
+    let mut sold = None;
+    for (i, entry) in player.inventory.entries.iter().take(rows).enumerate() {
+        let row_y = y + 45.0 + i as f32 * row_height;
+        draw_text(&format!("{} x{}", entry.item.name, entry.count), x + padding, row_y + 18.0, 20.0, entry.item.rarity.color());
+        if sell_button(Vec2::new(x + panel_width - 95.0, row_y), ApplyShopItem::sell_price(&entry.item), nav) {
+            sold = Some(entry.item.clone());
+        }
+    }
+
+    if let Some(item) = sold {
+        ApplyShopItem::sell_item(&ApplyShopItem { item }, player);
+    }
 }
 
-fn reroll_button(position: Vec2, texture: &Texture2D, player: &Player, shop: &Shop) -> bool {
+fn sell_button(position: Vec2, refund: u32, nav: &mut Navigation) -> bool {
+    let button_dim = Vec2::new(85.0, 24.0);
+    let text = format!("SELL +{}", refund);
+    let is_hovered = utilities::hovering_over(position, button_dim);
+    let (focused, activated) = nav.widget(is_hovered);
+    let button_color = if is_hovered || focused { GRAY } else { LIGHTGRAY };
+    draw_rectangle(position.x, position.y, button_dim.x, button_dim.y, button_color);
+    if focused {
+        navigation::draw_focus(position, button_dim);
+    }
+    let text_pos = utilities::center_text(&text, 18, button_dim, position);
+    draw_text(&text, text_pos.x, text_pos.y, 18.0, BLACK);
+    activated
+}
+
+pub fn income_panel(income: &Income) {
+    let x = 10.0;
+    let y = screen_height() - 190.0;
+    let panel_width = 250.0;
+    let panel_height = 180.0;
+    let padding = 20.0;
+    let mut y_offset = y + padding + 10.0;
+
+    // Draw the panel background with a border
+    draw_rectangle(x - 5.0, y - 5.0, panel_width + 10.0, panel_height + 10.0, BLACK);
+    draw_rectangle(x, y, panel_width, panel_height, WHITE);
+
+    draw_text("Last Wave Income", x + padding, y_offset, 30.0, BLACK);
+
+    y_offset += 35.0;
+    draw_text(&format!("Collected: {}", income.collected), x + padding, y_offset, 20.0, BLACK);
+    y_offset += 25.0;
+    draw_text(&format!("Left on the ground: {}", income.harvested), x + padding, y_offset, 20.0, BLACK);
+    y_offset += 25.0;
+    draw_text(&format!("Wave bonus: {}", income.wave_bonus), x + padding, y_offset, 20.0, BLACK);
+    y_offset += 25.0;
+    draw_text(&format!("Interest: {}", income.interest), x + padding, y_offset, 20.0, BLACK);
+    y_offset += 30.0;
+    draw_text(&format!("Total: {}", income.total()), x + padding, y_offset, 24.0, DARKGREEN);
+}
+
+fn reroll_button(position: Vec2, texture: &Texture2D, player: &Player, shop: &Shop, nav: &mut Navigation) -> bool {
     let mut text: String = String::from("REROLL -");
     text.push_str(&shop.reroll_cost.to_string());
-    let can_purchase = can_purchase_item(shop.reroll_cost, &player);
+    let mut can_purchase = can_purchase_item(shop.reroll_cost, &player);
+    if player.stats.free_rerolls > 0 {
+        text = format!("REROLL FREE x{}", player.stats.free_rerolls);
+        can_purchase = true;
+    }
     let button_width = 200.0;
     let button_height = 40.0;
     let is_hovered = utilities::hovering_over(position, Vec2::new(button_width, button_height));
+    let (focused, activated) = nav.widget(is_hovered);
     let text_measurements = measure_text(&text, None, 40, 1.0);
-    let mut button_background = if is_hovered { GRAY } else { WHITE };
-    if is_hovered && !can_purchase {
+    let mut button_background = if is_hovered || focused { GRAY } else { WHITE };
+    if (is_hovered || focused) && !can_purchase {
         button_background = Color::new(1.0, 0.678, 0.643, 1.00);
     }
     draw_rectangle(position.x, position.y, button_width + 40.0, button_height, button_background);
+    if focused {
+        navigation::draw_focus(position, Vec2::new(button_width + 40.0, button_height));
+    }
     let text_pos = utilities::center_text(&text, 40, Vec2::new(button_width, button_height), position);
     draw_text(
         &text,
@@ -151,13 +338,50 @@ fn reroll_button(position: Vec2, texture: &Texture2D, player: &Player, shop: &Sh
             ..Default::default()
         },
     );
-    is_hovered && is_mouse_button_pressed(MouseButton::Left) && can_purchase
+    activated && can_purchase
+}
+
+// Disabled while the player's level doesn't allow any item of the guaranteed rarity yet
+fn guaranteed_reroll_button(position: Vec2, texture: &Texture2D, player: &Player, shop: &Shop, available: bool, nav: &mut Navigation) -> bool {
+    let cost = shop.reroll_cost * GUARANTEED_REROLL_MULTIPLIER;
+    let text = format!("{}+ -{}", GUARANTEED_RARITY.name().to_uppercase(), cost);
+    let can_purchase = available && can_purchase_item(cost, player);
+    let button_width = 200.0;
+    let button_height = 40.0;
+    let is_hovered = utilities::hovering_over(position, Vec2::new(button_width, button_height));
+    let (focused, activated) = nav.widget(is_hovered);
+    let text_measurements = measure_text(&text, None, 30, 1.0);
+    let mut button_background = if is_hovered || focused { GRAY } else { WHITE };
+    if (is_hovered || focused) && !can_purchase {
+        button_background = Color::new(1.0, 0.678, 0.643, 1.00);
+    }
+    if !available {
+        button_background = DARKGRAY;
+    }
+    draw_rectangle(position.x, position.y, button_width + 40.0, button_height, button_background);
+    draw_rectangle_lines(position.x, position.y, button_width + 40.0, button_height, 3.0, GUARANTEED_RARITY.color());
+    if focused {
+        navigation::draw_focus(position, Vec2::new(button_width + 40.0, button_height));
+    }
+    let text_pos = utilities::center_text(&text, 30, Vec2::new(button_width, button_height), position);
+    draw_text(&text, text_pos.x, text_pos.y, 30.0, BLACK);
+    draw_texture_ex(
+        texture,
+        text_pos.x + text_measurements.width + 10.0,
+        text_pos.y - 22.5,
+        WHITE,
+        DrawTextureParams {
+            dest_size: Some(Vec2::new(30.0, 30.0)),
+            ..Default::default()
+        },
+    );
+    activated && can_purchase
 }
 
-fn buy_button(position: Vec2, texture: &Texture2D, player: &Player, cost: u32) -> bool {
+// Draws the buy button and returns whether the mouse is over it
+fn buy_button(position: Vec2, texture: &Texture2D) -> bool {
     let mut texture_width = 65.0;
     let mut texture_height = 65.0;
-    let can_purchase = can_purchase_item(cost, &player);
     let is_hovered = utilities::hovering_over(position, Vec2::new(texture_width, texture_height));
     if is_hovered {
         texture_width = 80.0;
@@ -173,7 +397,7 @@ fn buy_button(position: Vec2, texture: &Texture2D, player: &Player, cost: u32) -
             ..Default::default()
         },
     );
-    is_hovered && is_mouse_button_pressed(MouseButton::Left) && can_purchase
+    is_hovered
 }
 
 pub fn player_money(player: &Player, texture: &Texture2D) {
@@ -217,13 +441,17 @@ fn purchase_item(item: &Item, player: &mut Player) {
     ApplyShopItem::apply_item(&ApplyShopItem { item }, player);
 }
 
-fn start_next_wave() -> bool {
+fn start_next_wave(nav: &mut Navigation) -> bool {
     let mut button_dim = Vec2::new(300.0, 60.0);
     let text = "START NEXT WAVE";
     let position = Vec2::new(screen_width() - 310.0, screen_height() - 70.0);
     let is_hovered = utilities::hovering_over(position, Vec2::new(button_dim.x, button_dim.y));
-    let button_color = if is_hovered { GRAY } else { LIGHTGRAY };
+    let (focused, activated) = nav.widget(is_hovered);
+    let button_color = if is_hovered || focused { GRAY } else { LIGHTGRAY };
     draw_rectangle(position.x, position.y, button_dim.x, button_dim.y, button_color);
+    if focused {
+        navigation::draw_focus(position, button_dim);
+    }
     let text_pos = utilities::center_text(&text, 40, Vec2::new(button_dim.x, button_dim.y), position);
     draw_text(
         &text,
@@ -232,5 +460,5 @@ fn start_next_wave() -> bool {
         40.0,
         BLACK,
     );
-    is_hovered && is_mouse_button_pressed(MouseButton::Left)
+    activated
 }
\ No newline at end of file
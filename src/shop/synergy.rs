@@ -0,0 +1,197 @@
+use crate::player::Player;
+use crate::shop::{Attributes, Item};
+use crate::shop::logic::ApplyShopItem;
+
+pub struct SynergyTier {
+    pub required: u32,
+    pub description: String,
+    pub attributes: Attributes,
+}
+
+// A bonus granted for owning several items that share a tag
+pub struct Synergy {
+    pub tag: String,
+    pub name: String,
+    pub tiers: Vec<SynergyTier>,
+}
+
+pub struct SynergyProgress {
+    pub name: String,
+    pub owned: u32,
+    pub active_tiers: usize,
+    pub next: Option<(u32, String)>,
+}
+
+fn tier(required: u32, description: &str, attribute_type: &str, quantity: u32) -> SynergyTier {
+    SynergyTier {
+        required,
+        description: description.to_string(),
+        attributes: Attributes {
+            attribute_type: attribute_type.to_string(),
+            quantity,
+            duration: 0,
+        },
+    }
+}
+
+pub fn synergies() -> Vec<Synergy> {
+    vec![
+        Synergy {
+            tag: "crit".to_string(),
+            name: "Sharpshooter".to_string(),
+            tiers: vec![
+                tier(2, "+5% critical chance", "critical_chance", 5),
+                tier(4, "+25% critical damage", "critical_damage", 25),
+            ],
+        },
+        Synergy {
+            tag: "speed".to_string(),
+            name: "Quicksilver".to_string(),
+            tiers: vec![
+                tier(2, "+20 movement speed", "movement_speed", 20),
+                tier(4, "+10% attack speed", "attack_speed", 10),
+            ],
+        },
+        Synergy {
+            tag: "explosive".to_string(),
+            name: "Demolition".to_string(),
+            tiers: vec![
+                tier(2, "+25 damage radius", "damage_radius", 25),
+                tier(4, "+1 AoE target", "aoe_targets", 1),
+            ],
+        },
+        Synergy {
+            tag: "defense".to_string(),
+            name: "Fortress".to_string(),
+            tiers: vec![
+                tier(2, "+20 defense", "defense", 20),
+                tier(4, "+50 defense", "defense", 50),
+            ],
+        },
+        Synergy {
+            tag: "precision".to_string(),
+            name: "Marksman".to_string(),
+            tiers: vec![
+                tier(2, "+50 weapon range", "damage_range", 50),
+                tier(4, "+10 weapon damage", "weapon_damage", 10),
+            ],
+        },
+        Synergy {
+            tag: "arsenal".to_string(),
+            name: "Armory".to_string(),
+            tiers: vec![
+                tier(2, "+10 weapon damage", "weapon_damage", 10),
+                tier(4, "+100 bullet speed", "bullet_speed", 100),
+            ],
+        },
+        Synergy {
+            tag: "greed".to_string(),
+            name: "Treasure Hunter".to_string(),
+            tiers: vec![
+                tier(2, "+50 pickup radius", "pickup_radius", 50),
+                tier(4, "+100 pickup speed", "pickup_speed", 100),
+            ],
+        },
+    ]
+}
+
+fn owned_with_tag(player: &Player, tag: &str) -> u32 {
+    player.inventory.entries.iter()
+        .filter(|entry| entry.item.tags.iter().any(|item_tag| item_tag == tag))
+        .map(|entry| entry.count)
+        .sum()
+}
+
+// Applies newly reached tiers and reverts tiers lost by selling, call after the inventory changes
+pub fn refresh(player: &mut Player) {
+    for synergy in synergies() {
+        let owned = owned_with_tag(player, &synergy.tag);
+        let reached = synergy.tiers.iter().filter(|tier| tier.required <= owned).count();
+        let active = player.inventory.active_synergy_tiers(&synergy.tag);
+
+        for tier in synergy.tiers.iter().take(reached).skip(active) {
+            ApplyShopItem::apply_attributes(&tier.attributes, player);
+        }
+        for tier in synergy.tiers.iter().take(active).skip(reached) {
+            ApplyShopItem::revert_attributes(&tier.attributes, player);
+        }
+        player.inventory.set_active_synergy_tiers(&synergy.tag, reached);
+    }
+}
+
+// Descriptions of synergy tiers that buying this item would unlock
+pub fn unlocked_by(item: &Item, player: &Player) -> Vec<String> {
+    // Consumables never reach the inventory so they can't count towards a synergy
+    if item.is_consumable() {
+        return Vec::new();
+    }
+    synergies().into_iter()
+        .filter(|synergy| item.tags.contains(&synergy.tag))
+        .filter_map(|synergy| {
+            let owned = owned_with_tag(player, &synergy.tag);
+            synergy.tiers.iter()
+                .find(|tier| tier.required == owned + 1)
+                .map(|tier| format!("{}: {}", synergy.name, tier.description))
+        })
+        .collect()
+}
+
+// Synergies the player has at least one item towards
+pub fn progress(player: &Player) -> Vec<SynergyProgress> {
+    synergies().into_iter()
+        .filter_map(|synergy| {
+            let owned = owned_with_tag(player, &synergy.tag);
+            if owned == 0 {
+                return None;
+            }
+            let active_tiers = player.inventory.active_synergy_tiers(&synergy.tag);
+            let next = synergy.tiers.get(active_tiers)
+                .map(|tier| (tier.required, tier.description.clone()));
+            Some(SynergyProgress {
+                name: synergy.name,
+                owned,
+                active_tiers,
+                next,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use macroquad::math::Vec2;
+    use super::*;
+    use crate::shop::Rarity;
+    use crate::sprite::Sprite;
+
+    fn crit_item(name: &str) -> ApplyShopItem {
+        ApplyShopItem {
+            item: Item {
+                texture_name: "love_potion".to_string(),
+                name: name.to_string(),
+                price: 50,
+                rarity: Rarity::Common,
+                description: String::new(),
+                level_requirement: 1,
+                tags: vec!["crit".to_string()],
+                attributes: Attributes { attribute_type: "movement_speed".to_string(), quantity: 10, duration: 0 },
+            },
+        }
+    }
+
+    #[test]
+    fn selling_below_a_tier_takes_its_bonus_back() {
+        let mut player = Player::with_sprite(Sprite::placeholder(Vec2::new(50.0, 50.0)));
+        let critical_chance = player.stats.critical_chance;
+
+        crit_item("Lens").apply_item(&mut player);
+        assert_eq!(player.stats.critical_chance, critical_chance);
+        crit_item("Scope").apply_item(&mut player);
+        assert_eq!(player.inventory.active_synergy_tiers("crit"), 1);
+        assert!(player.stats.critical_chance > critical_chance);
+
+        crit_item("Scope").sell_item(&mut player);
+        assert_eq!(player.inventory.active_synergy_tiers("crit"), 0);
+        assert!((player.stats.critical_chance - critical_chance).abs() < 0.0001);
+    }
+}
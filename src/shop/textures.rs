@@ -5,5 +5,6 @@ pub(crate) async fn get_textures() -> ShopTextures {
     texture_map.add_texture("love_potion", "assets/shop/love_potion.png").await;
     texture_map.add_texture("buy_button", "assets/shop/buy_button.png").await;
     texture_map.add_texture("money_notes", "assets/money/money_notes.png").await;
+    texture_map.add_texture("money_bag", "assets/money/money_bag.png").await;
     texture_map
 }
\ No newline at end of file
@@ -35,10 +35,26 @@ impl Sprite {
         }
     }
 
-    pub fn update(&mut self) {
+    // A single frame sprite that never touches the graphics context, for tests that need a player
+    #[cfg(test)]
+    pub fn placeholder(size: Vec2) -> Self {
+        let texture = Texture2D::from_miniquad_texture(miniquad::TextureId::from_raw_id(miniquad::RawId::OpenGl(0)));
+        Sprite {
+            texture,
+            size,
+            current_frame: 0,
+            frame_timer: 0.0,
+            total_number_of_frames: 1.0,
+            number_of_columns: 1.0,
+            frame_width: size.x,
+            frame_height: size.y,
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
         // Update the frame based on a timer
-        // get_frame_time() gives the time since last frame
-        self.frame_timer += get_frame_time();
+        // dt is the game time since last frame
+        self.frame_timer += dt;
 
         if self.frame_timer > 0.1 { // Change frame every 0.1 seconds (adjust as needed)
             self.frame_timer = 0.0;
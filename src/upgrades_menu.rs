@@ -0,0 +1,67 @@
+use macroquad::prelude::*;
+use crate::background;
+use crate::characters::CHARACTERS;
+use crate::menu::Button;
+use crate::navigation::Navigation;
+use crate::profile::{self, Upgrade, ITEM_UNLOCKS};
+
+const ROW_HEIGHT: f32 = 60.0;
+
+// Draws one frame of the between-runs upgrade screen, returns true when the player goes back.
+// Purchases are written to the profile file straight away.
+pub(crate) fn upgrades_screen(background_texture: &Texture2D, nav: &mut Navigation) -> bool {
+    background::draw(background_texture);
+    draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.7));
+
+    let profile = profile::current();
+    // The right column lists every unlock, the starting character is free so it is left out
+    let rows = CHARACTERS.len() - 1 + ITEM_UNLOCKS.len();
+    let top = screen_height() / 2.0 - rows as f32 * ROW_HEIGHT / 2.0;
+    let left_x = screen_width() / 2.0 - 600.0;
+    let right_x = screen_width() / 2.0 + 40.0;
+    draw_text("UPGRADES", screen_width() / 2.0 - 100.0, top - 70.0, 50.0, WHITE);
+    draw_text(&format!("Tokens: {}", profile.tokens), screen_width() / 2.0 - 100.0, top - 25.0, 32.0, GOLD);
+
+    for (i, upgrade) in Upgrade::ALL.into_iter().enumerate() {
+        let level = profile.upgrade_level(upgrade);
+        let label = format!("{} ({}/{})", upgrade.name(), level, upgrade.max_level());
+        let value = if level >= upgrade.max_level() { "MAXED".to_string() } else { format!("BUY {}", upgrade.cost(level)) };
+        if purchase_row(&label, &value, Vec2::new(left_x, top + i as f32 * ROW_HEIGHT), nav) {
+            profile::modify(|profile| profile.buy_upgrade(upgrade));
+        }
+    }
+
+    // Cycles through the unlocked characters only
+    let character_y = top + (Upgrade::ALL.len() as f32 + 1.0) * ROW_HEIGHT;
+    if purchase_row("Character", &profile.character.to_uppercase(), Vec2::new(left_x, character_y), nav) {
+        let unlocked: Vec<&str> = CHARACTERS.iter().map(|character| character.name).filter(|name| profile.is_unlocked(name)).collect();
+        let next = unlocked.iter().position(|name| *name == profile.character).map_or(0, |i| (i + 1) % unlocked.len());
+        profile::modify(|profile| profile.character = unlocked[next].to_string());
+    }
+    if let Some(character) = CHARACTERS.iter().find(|character| character.name == profile.character) {
+        draw_text(character.description, left_x, character_y + ROW_HEIGHT + 10.0, 24.0, LIGHTGRAY);
+    }
+
+    let unlocks = CHARACTERS.iter()
+        .filter(|character| character.cost > 0)
+        .map(|character| (character.name, character.cost))
+        .chain(ITEM_UNLOCKS);
+    for (i, (name, cost)) in unlocks.enumerate() {
+        let value = if profile.is_unlocked(name) { "UNLOCKED".to_string() } else { format!("UNLOCK {}", cost) };
+        if purchase_row(name, &value, Vec2::new(right_x, top + i as f32 * ROW_HEIGHT), nav) {
+            profile::modify(|profile| profile.buy_unlock(name));
+        }
+    }
+
+    let mut back_btn = Button::new("BACK");
+    back_btn.set_position(Vec2::new(screen_width() / 2.0 - 100.0, top + rows as f32 * ROW_HEIGHT + 20.0));
+    back_btn.draw(nav)
+}
+
+// A label with a button next to it, returns true when the button is used
+fn purchase_row(label: &str, value: &str, position: Vec2, nav: &mut Navigation) -> bool {
+    draw_text(label, position.x, position.y + 35.0, 28.0, WHITE);
+    let mut button = Button::new(value);
+    button.set_position(Vec2::new(position.x + 340.0, position.y));
+    button.draw(nav)
+}
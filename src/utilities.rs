@@ -1,50 +1,69 @@
-use rand::thread_rng;
-use rand::Rng;
+use std::cell::RefCell;
+use rand::{thread_rng, Rng, SeedableRng};
 use core::ops::*;
 use macroquad::input::mouse_position;
 use macroquad::math::Rect;
 use macroquad::prelude::{measure_text, Vec2};
-use rand::rngs::ThreadRng;
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
 
+thread_local! {
+    // All gameplay randomness comes from here so a run can be replayed from its seed
+    static RNG: RefCell<StdRng> = RefCell::new(StdRng::from_entropy());
+}
+
+// A fresh seed for a new run
+pub fn new_seed() -> u64 {
+    thread_rng().gen()
+}
+
+pub fn seed_rng(seed: u64) {
+    RNG.with(|rng| *rng.borrow_mut() = StdRng::seed_from_u64(seed));
+}
+
+fn with_rng<T>(f: impl FnOnce(&mut StdRng) -> T) -> T {
+    RNG.with(|rng| f(&mut rng.borrow_mut()))
+}
+
 pub fn random_position(range: (Range<f32>, Range<f32>)) -> Vec2 {
-    let mut rng = thread_rng();
     let (x_range, y_range) = range;
-    Vec2::new(rng.gen_range(x_range), rng.gen_range(y_range))
+    with_rng(|rng| Vec2::new(rng.gen_range(x_range), rng.gen_range(y_range)))
 }
 
 pub fn random_spawn_position(map_width: f32, map_height: f32) -> Vec2 {
-    let mut rng = rand::thread_rng();
-    let edge = rng.gen_range(0..4);
-    match edge {
-        0 => Vec2::new(rng.gen_range(0.0..map_width), 0.0),
-        1 => Vec2::new(map_width, rng.gen_range(0.0..map_height)),
-        2 => Vec2::new(rng.gen_range(0.0..map_width), map_height),
-        _ => Vec2::new(0.0, rng.gen_range(0.0..map_height)),
-    }
+    with_rng(|rng| {
+        let edge = rng.gen_range(0..4);
+        match edge {
+            0 => Vec2::new(rng.gen_range(0.0..map_width), 0.0),
+            1 => Vec2::new(map_width, rng.gen_range(0.0..map_height)),
+            2 => Vec2::new(rng.gen_range(0.0..map_width), map_height),
+            _ => Vec2::new(0.0, rng.gen_range(0.0..map_height)),
+        }
+    })
 }
 
 pub fn random_number(range: RangeInclusive<i32>) -> i32 {
-    let mut rng = thread_rng();
-    rng.gen_range(range)
+    with_rng(|rng| rng.gen_range(range))
+}
+
+pub fn random_float(range: Range<f32>) -> f32 {
+    with_rng(|rng| rng.gen_range(range))
 }
 
 pub fn is_critical_hit(crit_chance: f32) -> bool {
-    let mut rng = thread_rng();
-    rng.gen::<f32>() < crit_chance
+    with_rng(|rng| rng.gen::<f32>() < crit_chance)
 }
 
-pub fn rand_thread_rng() -> ThreadRng {
-    thread_rng()
+pub fn random_chance(chance: f32) -> bool {
+    with_rng(|rng| rng.gen::<f32>() < chance)
 }
 
 pub fn get_random_elements<T>(vec: &Vec<T>, count: usize) -> Vec<T>
 where
     T: Clone,
 {
-    let mut rng = thread_rng();
     let mut elements = vec.clone();
-    elements.shuffle(&mut rng);
+    with_rng(|rng| elements.shuffle(rng));
     elements.into_iter().take(count).collect()
 }
 
@@ -58,4 +77,28 @@ pub fn center_text(text: &str, font_size: u16, container_measurements: Vec2, con
 pub fn hovering_over(position: Vec2, dimensions: Vec2) -> bool {
     let button_rect = Rect::new(position.x, position.y, dimensions.x, dimensions.y);
     button_rect.contains(mouse_position().into())
-}
\ No newline at end of file
+}
+
+// Today's date as YYYY-MM-DD in UTC
+pub fn today() -> String {
+    let seconds = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    let (year, month, day) = civil_from_days((seconds / 86_400) as i64);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+// Converts days since 1970-01-01 to a calendar date (Howard Hinnant's algorithm)
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
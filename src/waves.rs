@@ -1,16 +1,66 @@
-use crate::enemy::{Enemies};
-use crate::player::Player;
-use crate::menu::Menu;
+use crate::enemy::{Enemies, Enemy, EnemyTextures};
+use crate::player::{Attack, DamageText, Player};
+use crate::menu::Button;
+use crate::navigation::Navigation;
 use macroquad::prelude::*;
-use crate::shop::{Item, Shop, ShopTextures};
+use crate::pickups::Pickups;
+use crate::shop::{Income, Item, Shop, ShopTextures};
 use crate::utilities;
+use crate::background;
+use crate::camera::Camera;
+use crate::clock::GameClock;
+use crate::history::{RunMode, RunRecord};
+use crate::profile;
+use crate::events::{self, GameEvent};
 
-pub(crate) struct Waves<'a> {
+// Coins left on the ground when the wave ends are collected at this rate
+const HARVEST_RATE: f32 = 0.75;
+const WAVE_BONUS: u32 = 100;
+// Interest paid on banked money at the end of every wave, capped so hoarding doesn't snowball
+const INTEREST_RATE: f32 = 0.05;
+const INTEREST_CAP: u32 = 50;
+const FIRST_WAVE_DURATION: f64 = 20.0;
+// Surviving this wave wins a standard run
+pub const FINAL_WAVE: u32 = 20;
+// Every endless wave multiplies enemy difficulty by this and adds to the score multiplier
+const ENDLESS_DIFFICULTY_GROWTH: f32 = 1.15;
+const ENDLESS_SCORE_STEP: f32 = 0.25;
+
+// Everything that belongs to a single run. A new run is started by building a fresh one.
+pub(crate) struct Waves {
+    // Everything random in the run is drawn from this seed, it is kept in the run history
+    pub seed: u64,
     pub waves: Wave,
-    pub player: &'a mut Player,
-    pub enemies: &'a mut Enemies,
-    pub menu: &'a mut Menu,
-    pub shop: &'a mut ShopDetails,
+    pub clock: GameClock,
+    pub player: Player,
+    pub enemies: Enemies,
+    pub shop: ShopDetails,
+    pub pickups: Pickups,
+    pub bullets: Vec<Attack>,
+    pub damage_text: DamageText,
+    // Set once the final wave is survived, the run only goes on past it in endless mode
+    pub won: bool,
+    pub endless: bool,
+}
+
+// How a run ended, decided on the game over screen
+pub(crate) enum RunOutcome {
+    Retry,
+    MainMenu,
+}
+
+// Choice on the victory screen
+pub(crate) enum VictoryChoice {
+    Endless,
+    MainMenu,
+}
+
+pub(crate) enum WaveEvent {
+    // The wave timer ran out, the shop opens before the next wave
+    Cleared,
+    // The final wave of a standard run was survived
+    Won,
+    PlayerDied,
 }
 
 pub struct Wave {
@@ -22,91 +72,334 @@ pub struct Wave {
 
 pub struct ShopDetails {
     pub shop_items: Vec<Item>,
+    // Parallel to shop_items, a locked slot survives rerolls and carries into the next wave
+    pub locked_slots: Vec<bool>,
     pub shop_textures: ShopTextures,
     pub shop: Shop,
 }
 
-impl<'a> Waves<'a> {
-    pub fn new(wave: u32, duration: f64, player: &'a mut Player, enemies: &'a mut Enemies, menu: &'a mut Menu, shop: &'a mut ShopDetails) -> Self {
+impl Waves {
+    pub async fn new(enemy_textures: Vec<EnemyTextures>, shop_textures: ShopTextures) -> Self {
+        let seed = utilities::new_seed();
+        utilities::seed_rng(seed);
+        let mut player = Player::new().await;
+        profile::apply_to_player(&mut player);
+        let enemies = Enemy::spawn_enemy_pool(enemy_textures).await;
+        let shop_items = Item::shop(player.stats.level.level, 1);
+        let shop = ShopDetails {
+            locked_slots: vec![false; shop_items.len()],
+            shop_items,
+            shop_textures,
+            shop: Shop::new(),
+        };
         Waves {
-            waves: Wave {
-                wave,
-                duration: Timer::new(duration),
-                spawn_start: 20,
-                spawn_increment: 5,
-            },
+            seed,
+            waves: Wave::new(1, FIRST_WAVE_DURATION),
+            clock: GameClock::new(),
             player,
             enemies,
-            menu,
-            shop
+            shop,
+            pickups: Pickups::new(),
+            bullets: Vec::new(),
+            damage_text: DamageText::new(),
+            won: false,
+            endless: false,
         }
     }
 
-    pub async fn update(&mut self) {
-        if self.waves.duration.has_elapsed() {
-            self.waves.wave += 1;
+    pub fn update(&mut self) -> Option<WaveEvent> {
+        let now = self.clock.time();
+        if self.waves.duration.has_elapsed(now) {
             self.waves.duration.stop();
-            self.reset_enemies();
-            Item::shop_menu(&mut self.shop.shop_items, &mut self.player, &self.shop.shop_textures, &mut self.shop.shop).await;
-            self.start_wave();
-            self.waves.duration.start();
-        } else {
-            self.game_over().await;
-            if !self.waves.duration.running {
-                self.waves.duration.start()
+            events::emit(GameEvent::WaveEnded { wave: self.waves.wave });
+            if !self.endless && self.waves.wave >= FINAL_WAVE {
+                self.won = true;
+                events::emit(GameEvent::RunWon);
+                return Some(WaveEvent::Won);
+            }
+            return Some(WaveEvent::Cleared);
+        }
+        if self.player.stats.health <= 0.0 {
+            return Some(WaveEvent::PlayerDied);
+        }
+        if !self.waves.duration.running {
+            self.waves.duration.start(now)
+        }
+        None
+    }
+
+    // Passes a gameplay event to every part of the run that reacts to it
+    pub fn handle_event(&mut self, event: &GameEvent) {
+        self.player.run_stats.handle_event(event);
+        self.pickups.handle_event(event);
+        self.damage_text.handle_event(event);
+        // A won run only gets its shop once it carries on into endless mode
+        if let GameEvent::WaveEnded { wave } = event {
+            if !self.won || self.endless {
+                self.end_wave(*wave);
             }
         }
     }
 
+    // Carries a won run on past the final wave, the shop opens before the first endless wave
+    pub fn start_endless(&mut self) {
+        self.endless = true;
+        self.end_wave(self.waves.wave);
+    }
+
+    // Enemy strength multiplier, grows exponentially with every wave past the final one
+    fn difficulty(&self) -> f32 {
+        ENDLESS_DIFFICULTY_GROWTH.powi(self.endless_waves() as i32)
+    }
+
+    pub fn score_multiplier(&self) -> f32 {
+        1.0 + ENDLESS_SCORE_STEP * self.endless_waves() as f32
+    }
+
+    pub fn score(&self) -> u32 {
+        (self.player.run_stats.score(self.wave_reached()) as f32 * self.score_multiplier()) as u32
+    }
+
+    fn endless_waves(&self) -> u32 {
+        if self.endless { self.waves.wave.saturating_sub(FINAL_WAVE) } else { 0 }
+    }
+
+    // The wave the run got to, a standard win counts as the final wave
+    pub fn wave_reached(&self) -> u32 {
+        if self.won && !self.endless { FINAL_WAVE } else { self.waves.wave }
+    }
+
+    // Pays out the wave's income and restocks the shop before it opens
+    fn end_wave(&mut self, wave: u32) {
+        self.waves.wave = wave + 1;
+        self.reset_enemies();
+        self.collect_income();
+        self.shop.shop.reset_reroll_cost(self.waves.wave);
+        Item::restock(&mut self.shop.shop_items, &mut self.shop.locked_slots, self.player.stats.level.level, self.waves.wave);
+    }
+
+    // Called when the shop between waves is closed
+    pub fn start_next_wave(&mut self) {
+        self.enemies.difficulty = self.difficulty();
+        self.start_wave();
+        self.waves.duration.start(self.clock.time());
+        events::emit(GameEvent::WaveStarted { wave: self.waves.wave });
+    }
+
+    // Draws the game as it is without advancing anything, used underneath overlays
+    pub fn draw_scene(&mut self, background_texture: &Texture2D) {
+        Camera::init(&self.player);
+        background::draw(background_texture);
+        self.player.draw();
+
+        let money_texture = self.shop.shop_textures.get_texture("money_notes").unwrap();
+        let bag_texture = self.shop.shop_textures.get_texture("money_bag").unwrap();
+        for coin in self.pickups.coins.iter() {
+            coin.draw(if coin.bag { bag_texture } else { money_texture });
+        }
+        for orb in self.pickups.orbs.iter() {
+            orb.draw();
+        }
+        for magnet in self.pickups.magnets.iter() {
+            magnet.draw();
+        }
+        for enemy in self.enemies.enemy_pool.iter_mut() {
+            enemy.draw();
+        }
+        for bullet in self.bullets.iter() {
+            bullet.draw();
+        }
+        self.damage_text.draw();
+
+        Camera::ui();
+        self.draw_game_status();
+        self.player.draw_stats();
+        set_default_camera();
+    }
+
+    // The entry for this run in the local history
+    pub fn record(&self) -> RunRecord {
+        let build = self.player.inventory.entries.iter()
+            .map(|entry| if entry.count > 1 { format!("{} x{}", entry.item.name, entry.count) } else { entry.item.name.clone() })
+            .collect::<Vec<_>>()
+            .join(", ");
+        RunRecord {
+            date: utilities::today(),
+            seed: self.seed,
+            character: self.player.character.clone(),
+            wave: self.wave_reached(),
+            score: self.score(),
+            build,
+            mode: if self.endless { RunMode::Endless } else { RunMode::Standard },
+        }
+    }
+
     fn reset_enemies(&mut self) {
         for enemy in  self.enemies.enemy_pool.iter_mut() {
-            enemy.set_enemy_idle(self.player);
+            enemy.set_enemy_idle(&self.player);
         }
     }
 
+    fn collect_income(&mut self) {
+        // Counted at pickup, the shop can be opened mid-wave so the balance alone doesn't tell
+        let collected = self.player.run_stats.take_wave_coins();
+        let harvested = self.pickups.harvest_coins(HARVEST_RATE);
+        let interest = interest(self.player.stats.money);
+        let income = Income {
+            collected,
+            harvested,
+            wave_bonus: WAVE_BONUS,
+            interest,
+        };
+        self.player.stats.money += harvested + WAVE_BONUS + interest;
+        self.player.run_stats.record_earned(harvested + WAVE_BONUS + interest);
+        self.shop.shop.income = income;
+    }
+
     fn start_wave(&mut self) {
         self.player.stats.health = self.player.stats.max_health;
-        self.player.stats.money += 100;
+        self.spawn_wave_enemies();
+        self.waves.spawn_start += self.waves.spawn_increment;
+    }
+
+    fn spawn_wave_enemies(&mut self) {
         let mut spawn_count = 0;
+        // Endless waves bring more enemies on top of the usual increase
+        let spawn_target = (self.waves.spawn_start as f32 * self.enemies.difficulty) as u32;
         for enemy in self.enemies.enemy_pool.iter_mut() {
-            if !enemy.elite && spawn_count < self.waves.spawn_start {
-                enemy.set_enemy_active();
+            if !enemy.elite && spawn_count < spawn_target {
+                enemy.set_enemy_active(self.enemies.difficulty);
                 spawn_count += 1;
             }
         }
-        self.waves.spawn_start += self.waves.spawn_increment;
     }
 
     pub fn draw_game_status(&self) {
-        let text = format!("Wave: {} - Time: {:.2}", self.waves.wave, self.waves.duration.time_left());
-        let position = vec2(screen_width() / 2.0 - 200.0, 10.0);
+        let wave = if self.endless { format!("Endless wave: {}", self.waves.wave) } else { format!("Wave: {}/{}", self.waves.wave, FINAL_WAVE) };
+        let text = format!("{} - Time: {:.2}", wave, self.waves.duration.time_left(self.clock.time()));
+        let position = vec2(Camera::ui_size().x / 2.0 - 200.0, 10.0);
         let text_pos = utilities::center_text(&text, 40, Vec2::new(400.0, 50.0), position);
         draw_rectangle(position.x, position.y, 400.0, 50.0, BLACK);
         draw_text(&text, text_pos.x, text_pos.y, 40.0, WHITE);
     }
 
-    async fn game_over(&mut self) {
-        if self.player.stats.health <= 0.0 {
-            loop {
-                self.player.position = vec2(-10000.0, -10000.0);
-                draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.7));
-                let text = "GAME OVER".to_string();
-                let text_measurements = measure_text(&text, None, 100, 1.0);
-                let text_pos = Vec2::new(screen_width() / 2.0 - (text_measurements.width / 2.0), screen_height() / 2.0 - (text_measurements.height / 2.0));
-                draw_text(&text, text_pos.x, text_pos.y, 100.0, WHITE);
-
-                let is_hover_quit = utilities::hovering_over(Vec2::new((screen_width() / 2.0) - 100.0, (screen_height() / 2.0) + 100.0), Vec2::new(200.0, 50.0));
-                let quit_text = utilities::center_text("QUIT", 30, Vec2::new(200.0, 50.0), Vec2::new((screen_width() / 2.0) - 100.0, (screen_height() / 2.0) + 100.0));
-                let btn_quit_color = if is_hover_quit { GRAY } else { BLUE };
-                draw_rectangle((screen_width() / 2.0) - 100.0, (screen_height() / 2.0) + 100.0, 200.0, 50.0, btn_quit_color);
-                draw_text("QUIT", quit_text.x, quit_text.y, 30.0, WHITE);
-
-                if is_hover_quit && is_mouse_button_pressed(MouseButton::Left) {
-                    std::process::exit(0);
-                }
-
-                next_frame().await;
-            }
+    // Game over overlay with the run summary, returns the player's choice once one of the buttons is used
+    pub fn game_over(&mut self, nav: &mut Navigation) -> Option<RunOutcome> {
+        self.player.position = vec2(-10000.0, -10000.0);
+        self.draw_summary_screen("GAME OVER");
+
+        let mut retry_btn = Button::new("RETRY");
+        let mut menu_btn = Button::new("MAIN MENU");
+        retry_btn.set_position(Vec2::new((screen_width() / 2.0) - 210.0, screen_height() - 100.0));
+        menu_btn.set_position(Vec2::new((screen_width() / 2.0) + 10.0, screen_height() - 100.0));
+        if retry_btn.draw(nav) {
+            Some(RunOutcome::Retry)
+        } else if menu_btn.draw(nav) {
+            Some(RunOutcome::MainMenu)
+        } else {
+            None
+        }
+    }
+
+    // Shown after the final wave of a standard run, the player can keep going in endless mode
+    pub fn victory(&mut self, nav: &mut Navigation) -> Option<VictoryChoice> {
+        self.draw_summary_screen("VICTORY");
+
+        let mut endless_btn = Button::new("ENDLESS");
+        let mut menu_btn = Button::new("MAIN MENU");
+        endless_btn.set_position(Vec2::new((screen_width() / 2.0) - 210.0, screen_height() - 100.0));
+        menu_btn.set_position(Vec2::new((screen_width() / 2.0) + 10.0, screen_height() - 100.0));
+        if endless_btn.draw(nav) {
+            Some(VictoryChoice::Endless)
+        } else if menu_btn.draw(nav) {
+            Some(VictoryChoice::MainMenu)
+        } else {
+            None
+        }
+    }
+
+    fn draw_summary_screen(&self, title: &str) {
+        draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.85));
+        let text_measurements = measure_text(title, None, 80, 1.0);
+        draw_text(title, screen_width() / 2.0 - (text_measurements.width / 2.0), 110.0, 80.0, WHITE);
+
+        self.draw_run_summary(Vec2::new(screen_width() / 2.0 - 420.0, 180.0));
+        self.draw_final_stats(Vec2::new(screen_width() / 2.0 + 60.0, 180.0));
+    }
+
+    pub fn tokens(&self) -> u32 {
+        self.player.run_stats.tokens(self.wave_reached())
+    }
+
+    fn draw_run_summary(&self, position: Vec2) {
+        let stats = &self.player.run_stats;
+        let crit_rate = if stats.hits > 0 { stats.critical_hits as f32 / stats.hits as f32 * 100.0 } else { 0.0 };
+        let mut lines = vec![
+            format!("Score: {} (x{:.2})", self.score(), self.score_multiplier()),
+            format!("Tokens earned: {}", self.tokens()),
+            format!("Wave reached: {}", self.wave_reached()),
+            format!("Time survived: {}", format_time(self.clock.time())),
+            format!("Enemies killed: {}", stats.total_kills()),
+        ];
+        for (kind, kills) in stats.kills.iter() {
+            lines.push(format!("    {}: {}", kind, kills));
+        }
+        lines.extend([
+            format!("Damage dealt: {:.0}", stats.damage_dealt),
+            format!("Critical hits: {} ({:.1}%)", stats.critical_hits, crit_rate),
+            format!("Damage taken: {:.0}", stats.damage_taken),
+            format!("Money earned: {}", stats.money_earned),
+            format!("Money spent: {}", stats.money_spent),
+            format!("Items bought: {}", stats.items_bought),
+        ]);
+        draw_summary_column("Run", &lines, position);
+    }
+
+    fn draw_final_stats(&self, position: Vec2) {
+        let player = &self.player;
+        let lines = [
+            format!("Character: {}", player.character),
+            format!("Level: {}", player.stats.level.level),
+            format!("Max Health: {:.0}", player.stats.max_health),
+            format!("Movement Speed: {:.1}", player.stats.movement_speed),
+            format!("Defense: {}", player.stats.defense),
+            format!("Critical Chance: {:.1}%", player.stats.critical_chance * 100.0),
+            format!("Critical Damage: {:.1}%", player.stats.critical_damage * 100.0),
+            format!("Attack Speed: {:.2}", player.attack_interval()),
+            format!("Weapon Damage: {}", player.weapon.damage),
+            format!("Weapon Count: {}", player.weapon.count),
+            format!("Weapon Splash Radius: {}", player.weapon.damage_radius),
+            format!("Weapon Range: {}", player.weapon.range),
+            format!("AoE Targets: {}", player.weapon.aoe_count),
+        ];
+        draw_summary_column("Final Stats", &lines, position);
+    }
+}
+
+fn draw_summary_column(title: &str, lines: &[String], position: Vec2) {
+    draw_text(title, position.x, position.y, 40.0, GOLD);
+    for (i, line) in lines.iter().enumerate() {
+        draw_text(line, position.x, position.y + 45.0 + i as f32 * 30.0, 26.0, WHITE);
+    }
+}
+
+// Minutes and seconds, e.g. 3:07
+fn format_time(seconds: f64) -> String {
+    let seconds = seconds as u32;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+fn interest(banked: u32) -> u32 {
+    ((banked as f32 * INTEREST_RATE) as u32).min(INTEREST_CAP)
+}
+
+impl Wave {
+    fn new(wave: u32, duration: f64) -> Self {
+        Wave {
+            wave,
+            duration: Timer::new(duration),
+            spawn_start: 20,
+            spawn_increment: 5,
         }
     }
 }
@@ -121,13 +414,13 @@ impl Timer {
     fn new(interval: f64) -> Self {
         Timer {
             interval,
-            last_time: get_time(),
+            last_time: 0.0,
             running: false,
         }
     }
 
-    fn start(&mut self) {
-        self.last_time = get_time();
+    fn start(&mut self, now: f64) {
+        self.last_time = now;
         self.running = true;
     }
 
@@ -135,12 +428,11 @@ impl Timer {
         self.running = false;
     }
 
-    fn has_elapsed(&mut self) -> bool {
+    fn has_elapsed(&mut self, current_time: f64) -> bool {
         if !self.running {
             return false;  // If the timer is not running, don't check
         }
 
-        let current_time = get_time();
         if current_time - self.last_time >= self.interval {
             self.running = false;  // Stop the timer when it elapses
             true
@@ -149,13 +441,33 @@ impl Timer {
         }
     }
 
-    fn time_left(&self) -> f64 {
+    fn time_left(&self, current_time: f64) -> f64 {
         if !self.running {
             return 0.0;  // If the timer isn't running, return 0
         }
 
-        let current_time = get_time();
         let time_passed = current_time - self.last_time;
         (self.interval - time_passed).max(0.0)  // Don't let the time go negative
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::money::Money;
+
+    #[test]
+    fn interest_stops_at_the_cap() {
+        assert_eq!(interest(400), 20);
+        assert_eq!(interest(1000), INTEREST_CAP);
+        assert_eq!(interest(5000), INTEREST_CAP);
+    }
+
+    #[test]
+    fn coins_left_on_the_ground_are_harvested_at_the_rate() {
+        let mut pickups = Pickups::new();
+        pickups.coins.push(Money::new(Vec2::ZERO, 60));
+        pickups.coins.push(Money::new(Vec2::ZERO, 40));
+        assert_eq!(pickups.harvest_coins(HARVEST_RATE), 75);
+        assert!(pickups.coins.is_empty());
+    }
+}